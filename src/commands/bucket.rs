@@ -0,0 +1,255 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use argh::FromArgs;
+use console::style;
+
+use super::prelude::*;
+use crate::core::{buckets::*, config::*, install::Receipt, lock::LockMode};
+use crate::error::ScoopieError;
+use crate::utils::Absolute;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Manage buckets (add, rm, list, known, rename, priority)
+#[argh(subcommand, name = "bucket")]
+pub struct BucketCommand {
+    #[argh(subcommand)]
+    cmd: BucketSubCommand,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand)]
+enum BucketSubCommand {
+    Add(AddBucket),
+    Known(KnownBuckets),
+    List(ListBuckets),
+    Priority(PrioritizeBuckets),
+    Remove(RemoveBucket),
+    Rename(RenameBucket),
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Add a bucket and sync it, from a git url, a local git repository or a plain directory of
+/// manifests (the source can be left out for known buckets)
+#[argh(subcommand, name = "add")]
+struct AddBucket {
+    #[argh(positional)]
+    name: String,
+
+    #[argh(positional)]
+    url: Option<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// List Scoop's official buckets
+#[argh(subcommand, name = "known")]
+struct KnownBuckets {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// List configured buckets
+#[argh(subcommand, name = "list")]
+struct ListBuckets {}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Show the order buckets are looked into for apps, or set it (unlisted buckets follow by name)
+#[argh(subcommand, name = "priority")]
+struct PrioritizeBuckets {
+    #[argh(positional)]
+    buckets: Vec<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Remove a bucket
+#[argh(subcommand, name = "rm")]
+struct RemoveBucket {
+    #[argh(positional)]
+    name: String,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Rename a bucket, keeping what's synced and installed from it
+#[argh(subcommand, name = "rename")]
+struct RenameBucket {
+    #[argh(positional)]
+    from: String,
+
+    #[argh(positional)]
+    to: String,
+}
+
+impl BucketCommand {
+    pub fn lock_mode(&self) -> LockMode {
+        match &self.cmd {
+            BucketSubCommand::Known(_) | BucketSubCommand::List(_) => LockMode::Shared,
+            BucketSubCommand::Priority(x) if x.buckets.is_empty() => LockMode::Shared,
+            _ => LockMode::Exclusive,
+        }
+    }
+}
+
+impl ExecuteCommand for BucketCommand {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        match &self.cmd {
+            BucketSubCommand::Add(x) => x.exec(),
+            BucketSubCommand::Known(x) => x.exec(),
+            BucketSubCommand::List(x) => x.exec(),
+            BucketSubCommand::Priority(x) => x.exec(),
+            BucketSubCommand::Remove(x) => x.exec(),
+            BucketSubCommand::Rename(x) => x.exec(),
+        }
+    }
+}
+
+impl ExecuteCommand for AddBucket {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        let url = match (&self.url, known_bucket_url(&self.name)) {
+            (Some(url), _) => url.clone(),
+            (None, Some(url)) => url.into(),
+            (None, None) => return Err(ScoopieError::BucketUrlRequired(self.name.clone())),
+        };
+
+        // Local buckets are synced from wherever scoopie runs, so relative paths won't do.
+        let url = match PathBuf::from(&url) {
+            path if path.exists() => path.absolute()?.to_string_lossy().to_string(),
+            _ => url,
+        };
+
+        let status = Buckets::add(&self.name, &url)?;
+        println!("{status}");
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for KnownBuckets {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        let added = Config::read()?.list_buckets();
+
+        KNOWN_BUCKETS.iter().for_each(|(name, url)| {
+            let mut line = format!("{}  {url}", style(name).blue());
+
+            if added.iter().any(|added| added == name) {
+                line.push_str(&format!("  {}", style("[added]").green()));
+            }
+
+            println!("{line}");
+        });
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for ListBuckets {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Buckets::summary()?.iter().for_each(|(name, url, entry)| {
+            let synced = match entry.synced_at {
+                0 if entry.commit_id.is_empty() => String::from("never synced"),
+                0 => String::from("synced at an unknown time"),
+                at => format!("synced {}", ago(now.saturating_sub(at))),
+            };
+
+            let commit = entry.commit_id.get(..7).unwrap_or(&entry.commit_id);
+            let apps = match entry.apps {
+                Some(apps) => format!("{apps} app(s)"),
+                None => String::from("? app(s)"),
+            };
+
+            println!(
+                "{}  {url}\n  {}  {}  {}",
+                style(name).blue(),
+                style(commit).yellow(),
+                style(apps).magenta(),
+                style(synced).white()
+            );
+        });
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for PrioritizeBuckets {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        if !self.buckets.is_empty() {
+            Buckets::prioritize(&self.buckets)?;
+        }
+
+        Config::read()?
+            .list_buckets()
+            .iter()
+            .enumerate()
+            .for_each(|(i, name)| println!("{}. {}", i + 1, style(name).blue()));
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for RemoveBucket {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        Buckets::remove(&self.name)?;
+        println!("Removed bucket {}", self.name);
+
+        let orphaned = Receipt::installed()?
+            .into_iter()
+            .filter(|receipt| receipt.bucket == self.name)
+            .map(|receipt| receipt.app)
+            .collect::<Vec<_>>();
+
+        if !orphaned.is_empty() {
+            eprintln!(
+                "{}",
+                style(format!(
+                    "Installed apps from {} won't receive updates from it anymore: {}",
+                    self.name,
+                    orphaned.join(", ")
+                ))
+                .yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for RenameBucket {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        // Receipts remember where apps came from, so updates keep looking in the right place.
+        // They're retargeted first, running the rename again picks up where a failed one stopped.
+        let retarget = || {
+            let app_dir = Config::app_dir()?;
+
+            Receipt::installed()?.iter().try_for_each(|installed| {
+                Receipt::versions(&installed.app)?
+                    .into_iter()
+                    .filter(|receipt| receipt.bucket == self.from)
+                    .try_for_each(|mut receipt| {
+                        receipt.bucket = self.to.clone();
+
+                        let version_dir = app_dir.join(&receipt.app).join(receipt.version.as_str());
+                        receipt.write(&version_dir)
+                    })
+            })
+        };
+
+        Buckets::rename(&self.from, &self.to, retarget)?;
+
+        println!("Renamed bucket {} to {}", self.from, self.to);
+
+        Ok(())
+    }
+}
+
+fn ago(secs: u64) -> String {
+    match secs {
+        0..60 => String::from("just now"),
+        60..3600 => format!("{} mins ago", secs / 60),
+        3600..86400 => format!("{} hours ago", secs / 3600),
+        _ => format!("{} days ago", secs / 86400),
+    }
+}
@@ -0,0 +1,41 @@
+use argh::FromArgs;
+
+use super::prelude::*;
+use crate::core::install::Receipt;
+use crate::error::ScoopieError;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Keep apps at their current version, "scoopie update" skips them until they're unheld
+#[argh(subcommand, name = "hold")]
+pub struct HoldCommand {
+    #[argh(positional)]
+    apps: Vec<String>,
+}
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Let "scoopie update" update held apps again
+#[argh(subcommand, name = "unhold")]
+pub struct UnholdCommand {
+    #[argh(positional)]
+    apps: Vec<String>,
+}
+
+impl ExecuteCommand for HoldCommand {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        self.apps.iter().try_for_each(|app| {
+            let receipt = Receipt::hold(&app.trim().to_lowercase(), true)?;
+            println!("{} is now held at v{}", receipt.app, receipt.version);
+            Ok(())
+        })
+    }
+}
+
+impl ExecuteCommand for UnholdCommand {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        self.apps.iter().try_for_each(|app| {
+            let receipt = Receipt::hold(&app.trim().to_lowercase(), false)?;
+            println!("{} is no longer held", receipt.app);
+            Ok(())
+        })
+    }
+}
@@ -1,9 +1,12 @@
-use std::unimplemented;
+use std::fs;
 
 use argh::FromArgs;
+use console::style;
+use indicatif::HumanBytes;
 
 use super::prelude::*;
 
+use crate::core::{buckets::*, config::*, download::Downloader, install::Receipt};
 use crate::error::ScoopieError;
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -17,10 +20,166 @@ pub struct InfoCommand {
     /// show mainfest of app
     show_mainfest: bool,
 }
+
+impl InfoCommand {
+    fn field(name: &str, value: impl AsRef<str>) {
+        let value = value.as_ref();
+
+        if !value.is_empty() {
+            let name = format!("{name}:");
+            println!("{}{value}", style(format!("{name:<15}")).bold());
+        }
+    }
+}
+
 impl ExecuteCommand for InfoCommand {
     fn exec(&self) -> Result<(), ScoopieError> {
-        println!("{:?}", self);
+        let query = match &self.app {
+            Some(app) => app.trim().to_lowercase(),
+            None => {
+                eprintln!("App argument required");
+                return Ok(());
+            }
+        };
+
+        let (bucket, app_name) = match query.split_once('/') {
+            Some((bucket, app_name)) => (Some(bucket), app_name),
+            None => (None, query.as_str()),
+        };
+
+        let current = Receipt::current(app_name).ok();
+        let installed = Receipt::versions(app_name)?;
+
+        // Installed apps can still be described from their receipts and installed manifest.
+        let available = match Buckets::query_app(app_name) {
+            Ok(query) => query
+                .find_all(app_name)
+                .into_iter()
+                .filter(|(bucket_name, _)| bucket.is_none_or(|bucket| bucket == bucket_name))
+                .collect::<Vec<_>>(),
+            Err(err) if !installed.is_empty() => {
+                eprintln!("{}", style(format!("Skipping buckets: {err}")).yellow());
+                vec![]
+            }
+            Err(err) => return Err(err),
+        };
+
+        // The current version's manifest, or that of any other installed version.
+        let app_dir = Config::app_dir()?.join(app_name);
+        let installed_manifest = current.iter().chain(installed.iter()).find_map(|receipt| {
+            let path = app_dir.join(receipt.version.as_str()).join("manifest.json");
+            Manifest::try_from(path.clone())
+                .ok()
+                .map(|manifest| (path, manifest))
+        });
+
+        let installed = installed
+            .iter()
+            .map(|receipt| {
+                let is_current = current
+                    .as_ref()
+                    .is_some_and(|current| current.version.as_str() == receipt.version.as_str());
+
+                match is_current {
+                    true => format!("v{} ({}, current)", receipt.version, receipt.bucket),
+                    false => format!("v{} ({})", receipt.version, receipt.bucket),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        // Prefer what's actually installed, fall back to what the buckets offer.
+        let (manifest, manifest_path) = match (installed_manifest, available.first()) {
+            (Some((path, manifest)), _) => (manifest, Some(path)),
+            (None, Some((_, manifest))) => (manifest.clone(), None),
+            // Nothing left to describe it with but its receipts.
+            (None, None) if !installed.is_empty() && !self.show_mainfest => {
+                Self::field("Name", app_name);
+                Self::field("Installed", &installed);
+                return Ok(());
+            }
+            (None, None) => return Err(ScoopieError::NoAppFound(app_name.into())),
+        };
+
+        // Printed as it is on disk, not as Scoopie understood it.
+        if self.show_mainfest {
+            let content = match (manifest_path, available.first()) {
+                (Some(path), _) => {
+                    fs::read(&path).map_err(|_| ScoopieError::FailedToReadFile(path))?
+                }
+                (None, Some((bucket, _))) => Buckets::raw_manifest(bucket, app_name)?,
+                (None, None) => return Err(ScoopieError::NoAppFound(app_name.into())),
+            };
+
+            println!("{}", String::from_utf8_lossy(&content).trim_end());
+            return Ok(());
+        }
+
+        Self::field("Name", app_name);
+        Self::field("Description", &manifest.description);
+        Self::field("Homepage", &manifest.homepage);
+        Self::field("License", manifest.license());
+
+        Self::field(
+            "Available",
+            available
+                .iter()
+                .map(|(bucket, manifest)| format!("v{} ({bucket})", manifest.version))
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+
+        Self::field("Installed", &installed);
+
+        Self::field(
+            "Binaries",
+            manifest
+                .bin()
+                .iter()
+                .map(|bin| bin.alias.clone())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+
+        Self::field(
+            "Shortcuts",
+            manifest
+                .shortcuts()
+                .iter()
+                .filter_map(|shortcut| shortcut.get(1).cloned())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+
+        Self::field(
+            "Dependencies",
+            manifest
+                .depends()
+                .iter()
+                .map(|dependency| dependency.to_string())
+                .collect::<Vec<_>>()
+                .join(", "),
+        );
+
+        let sizes = Downloader::sizes(app_name, &manifest)?;
+
+        // Left out when there's nothing to download, e.g. for manifests which only run scripts.
+        Self::field(
+            "Download size",
+            match (sizes.is_empty(), sizes.iter().all(Option::is_some)) {
+                (true, _) => String::new(),
+                (false, true) => HumanBytes(sizes.iter().flatten().sum()).to_string(),
+                (false, false) => String::from("Unknown"),
+            },
+        );
+
+        let notes = manifest.notes();
+
+        if !notes.is_empty() {
+            println!("{}", style("Notes:").bold());
+            notes.iter().for_each(|note| println!("  {note}"));
+        }
 
-        unimplemented!();
+        Ok(())
     }
 }
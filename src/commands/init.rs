@@ -1,5 +1,5 @@
 use argh::FromArgs;
-use std::{fs::File, path::PathBuf};
+use std::path::{Path, PathBuf};
 
 use crate::{
     core::{buckets::write_default_metadata, config::*},
@@ -18,13 +18,13 @@ pub struct InitCommand {
 }
 
 impl InitCommand {
-    fn create_dirs(curr_dir: &PathBuf) -> Result<(), ScoopieError> {
+    fn create_dirs(curr_dir: &Path) -> Result<(), ScoopieError> {
         ["apps", "buckets", "cache", "persists", "shims"]
             .iter()
             .try_for_each(|dir| PathBuf::create(&curr_dir.join(dir)))
     }
 
-    fn init_scoopie(scoopie_path: &PathBuf) -> Result<(), ScoopieError> {
+    fn init_scoopie(scoopie_path: &Path) -> Result<(), ScoopieError> {
         let scoopie_dir = scoopie_path.join("apps/scoopie");
 
         if !scoopie_dir.exists() {
@@ -68,10 +68,12 @@ impl ExecuteCommand for InitCommand {
             Config::write(&scoopie_config)?;
         }
 
-        Pwsh::create_or_update(
+        let env = user_env(&scoopie_path);
+        env.set(
             "SCOOPIE_HOME",
             scoopie_path.as_path().to_str().unwrap_or_default(),
         )?;
+        env.add_to_path(&[scoopie_path.join("shims")])?;
 
         println!(
             "🎊 Congrats! Scoopie initialized.\nLocated at: {}\nConfig at: {}",
@@ -82,14 +84,3 @@ impl ExecuteCommand for InitCommand {
         Ok(())
     }
 }
-
-fn download(file: PathBuf, url: &str) -> Result<(), ScoopieError> {
-    let req = minreq::get(url);
-    let res = req.send().unwrap();
-
-    let mut file = File::create(file).unwrap();
-
-    std::io::copy(&mut res.as_bytes(), &mut file).unwrap();
-
-    Ok(())
-}
@@ -1,8 +1,12 @@
-use crate::core::{buckets::*, download::*, install::install};
+use crate::core::{
+    buckets::*,
+    download::*,
+    install::{install, script_runner},
+};
 
 use argh::FromArgs;
 
-use super::prelude::*;
+use super::{prelude::*, update::UpdateCommand};
 use crate::error::ScoopieError;
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -23,6 +27,14 @@ pub struct InstallCommand {
     #[argh(switch, short = 'a')]
     /// update all apps
     update_all: bool,
+
+    #[argh(switch)]
+    /// also install the first available app for each unmet suggestion
+    with_suggested: bool,
+
+    #[argh(switch)]
+    /// skip manifest scripts (hooks and installers)
+    no_scripts: bool,
 }
 
 impl ExecuteCommand for InstallCommand {
@@ -30,6 +42,8 @@ impl ExecuteCommand for InstallCommand {
         if self.sync {
             let status = Buckets::sync();
             println!("{:?}", status);
+        } else if self.update_all {
+            UpdateCommand::update(None, self.no_scripts)?;
         } else if self.download_only {
             match &self.app {
                 Some(app) => {
@@ -41,8 +55,14 @@ impl ExecuteCommand for InstallCommand {
                 }
             };
         } else {
-            let app = self.app.as_ref().unwrap();
-            let _ = install(app);
+            match &self.app {
+                Some(app) => install(
+                    app,
+                    self.with_suggested,
+                    script_runner(self.no_scripts).as_ref(),
+                )?,
+                None => eprintln!("App argument required"),
+            };
         }
 
         Ok(())
@@ -1,15 +1,107 @@
 use argh::FromArgs;
+use console::style;
+use regex_lite::Regex;
+use serde::Serialize;
 
 use super::prelude::*;
+use crate::core::{
+    buckets::{Buckets, Version},
+    install::Receipt,
+};
 use crate::error::ScoopieError;
 
 #[derive(FromArgs, PartialEq, Debug)]
 /// List all installed apps
 #[argh(subcommand, name = "list")]
-pub struct ListCommand {}
+pub struct ListCommand {
+    #[argh(positional)]
+    pattern: Option<String>,
+
+    #[argh(switch)]
+    /// print the list as JSON
+    json: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct ListEntry {
+    name: String,
+    version: Version,
+    bucket: String,
+    held: bool,
+    latest: Option<Version>,
+    outdated: bool,
+}
 
 impl ExecuteCommand for ListCommand {
     fn exec(&self) -> Result<(), ScoopieError> {
-        todo!()
+        let pattern = match &self.pattern {
+            Some(pat) => Some(Regex::new(pat).map_err(|_| ScoopieError::InvalidRegex(pat.into()))?),
+            None => None,
+        };
+
+        let receipts = Receipt::installed()?
+            .into_iter()
+            .filter(|receipt| pattern.as_ref().is_none_or(|re| re.is_match(&receipt.app)))
+            .collect::<Vec<_>>();
+
+        let names = receipts.iter().map(|r| r.app.clone()).collect::<Vec<_>>();
+
+        // Listing should still work when buckets aren't synced yet, only the update hints are lost.
+        let buckets = Buckets::query_apps(&names).ok();
+
+        let entries = receipts
+            .into_iter()
+            .map(|receipt| {
+                let latest = buckets.as_ref().and_then(|buckets| {
+                    buckets
+                        .get_app_from(&receipt.app, &receipt.bucket)
+                        .or_else(|| buckets.get_app(&receipt.app))
+                        .map(|manifest| manifest.version)
+                });
+
+                let outdated = latest
+                    .as_ref()
+                    .is_some_and(|latest| *latest > receipt.version);
+
+                ListEntry {
+                    name: receipt.app,
+                    version: receipt.version,
+                    bucket: receipt.bucket,
+                    held: receipt.held,
+                    latest,
+                    outdated,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        match self.json {
+            true => println!(
+                "{}",
+                serde_json::to_string_pretty(&entries).map_err(|_| ScoopieError::Unknown)?
+            ),
+            false => entries.iter().for_each(|entry| {
+                let mut line = format!(
+                    "{}/{}  {}",
+                    style(&entry.name).green(),
+                    style(&entry.bucket).blue(),
+                    style(format!("v{}", entry.version)).magenta()
+                );
+
+                if entry.held {
+                    line.push_str(&format!("  {}", style("[held]").yellow()));
+                }
+
+                if let (true, Some(latest)) = (entry.outdated, &entry.latest) {
+                    line.push_str(&format!(
+                        "  {}",
+                        style(format!("(v{latest} available)")).cyan()
+                    ));
+                }
+
+                println!("{line}");
+            }),
+        }
+
+        Ok(())
     }
 }
@@ -1,10 +1,14 @@
 use argh::FromArgs;
 
 use super::prelude::*;
+use crate::core::{
+    config::*,
+    install::{Receipt, Shims},
+};
 use crate::error::ScoopieError;
 
 #[derive(FromArgs, PartialEq, Debug)]
-/// Shows the location of specified app
+/// Shows the location of specified app or of the binary it provides
 #[argh(subcommand, name = "locate")]
 pub struct LocateCommand {
     #[argh(positional)]
@@ -13,6 +17,20 @@ pub struct LocateCommand {
 
 impl ExecuteCommand for LocateCommand {
     fn exec(&self) -> Result<(), ScoopieError> {
-        todo!()
+        let name = self.app.trim().to_lowercase();
+
+        let location = match Receipt::current(&name) {
+            Ok(receipt) => Config::app_dir()?.join(receipt.app).join("current"),
+            Err(_) => {
+                let (app, target) = Shims::resolve(&name)?;
+                // Keep stdout to the bare path so scripts can consume it directly.
+                eprintln!("\"{name}\" is provided by {app}");
+                target
+            }
+        };
+
+        println!("{}", location.display());
+
+        Ok(())
     }
 }
@@ -1,3 +1,5 @@
+mod bucket;
+mod hold;
 mod info;
 mod init;
 mod install;
@@ -7,11 +9,16 @@ mod nuke;
 mod prelude;
 mod query;
 mod remove;
+mod switch;
+mod update;
 
 use argh::FromArgs;
 
+use crate::core::lock::{Lock, LockMode};
 use crate::error::ScoopieError;
 
+use bucket::BucketCommand;
+use hold::{HoldCommand, UnholdCommand};
 use info::InfoCommand;
 use init::InitCommand;
 use install::InstallCommand;
@@ -20,6 +27,8 @@ use locate::LocateCommand;
 use nuke::NukeCommand;
 use query::QueryCommand;
 use remove::RemoveCommand;
+use switch::SwitchCommand;
+use update::UpdateCommand;
 
 pub trait ExecuteCommand {
     fn exec(&self) -> Result<(), ScoopieError>;
@@ -30,12 +39,24 @@ pub trait ExecuteCommand {
 pub struct Commands {
     #[argh(subcommand)]
     cmd: Command,
+
+    #[argh(switch)]
+    /// wait for other running scoopie operations to finish instead of failing
+    wait: bool,
 }
 
 impl ExecuteCommand for Commands {
     fn exec(&self) -> Result<(), ScoopieError> {
-        println!("{:?}", self);
-        self.cmd.exec()?;
+        let lock = match self.cmd.lock_mode() {
+            Some(mode) => Some(Lock::acquire(mode, self.wait)?),
+            None => None,
+        };
+
+        match (&self.cmd, lock) {
+            (Command::Nuke(x), Some(lock)) => x.exec_locked(lock)?,
+            (cmd, _lock) => cmd.exec()?,
+        }
+
         Ok(())
     }
 }
@@ -43,6 +64,8 @@ impl ExecuteCommand for Commands {
 #[derive(FromArgs, PartialEq, Debug)]
 #[argh(subcommand)]
 enum Command {
+    Bucket(BucketCommand),
+    Hold(HoldCommand),
     Info(InfoCommand),
     Init(InitCommand),
     Install(InstallCommand),
@@ -51,11 +74,36 @@ enum Command {
     Nuke(NukeCommand),
     Query(QueryCommand),
     Remove(RemoveCommand),
+    Switch(SwitchCommand),
+    Unhold(UnholdCommand),
+    Update(UpdateCommand),
+}
+
+impl Command {
+    /// How `$SCOOPIE_HOME` has to be locked while running, `None` for `init` which creates it.
+    fn lock_mode(&self) -> Option<LockMode> {
+        match self {
+            Command::Bucket(x) => Some(x.lock_mode()),
+            Command::Init(_) => None,
+            Command::Info(_) | Command::List(_) | Command::Locate(_) | Command::Query(_) => {
+                Some(LockMode::Shared)
+            }
+            Command::Hold(_)
+            | Command::Install(_)
+            | Command::Nuke(_)
+            | Command::Remove(_)
+            | Command::Switch(_)
+            | Command::Unhold(_)
+            | Command::Update(_) => Some(LockMode::Exclusive),
+        }
+    }
 }
 
 impl ExecuteCommand for Command {
     fn exec(&self) -> Result<(), ScoopieError> {
         match self {
+            Command::Bucket(x) => x.exec(),
+            Command::Hold(x) => x.exec(),
             Command::Info(x) => x.exec(),
             Command::Init(x) => x.exec(),
             Command::Install(x) => x.exec(),
@@ -64,6 +112,9 @@ impl ExecuteCommand for Command {
             Command::Nuke(x) => x.exec(),
             Command::Query(x) => x.exec(),
             Command::Remove(x) => x.exec(),
+            Command::Switch(x) => x.exec(),
+            Command::Unhold(x) => x.exec(),
+            Command::Update(x) => x.exec(),
         }
     }
 }
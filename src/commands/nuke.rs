@@ -1,7 +1,11 @@
 use argh::FromArgs;
-use std::{env, path::PathBuf};
+use std::{env, fs, path::PathBuf};
 
 use super::prelude::*;
+use crate::core::{
+    install::Receipt,
+    lock::{Lock, LockMode},
+};
 use crate::error::ScoopieError;
 use crate::utils::*;
 
@@ -12,9 +16,38 @@ pub struct NukeCommand {}
 
 impl ExecuteCommand for NukeCommand {
     fn exec(&self) -> Result<(), ScoopieError> {
+        self.exec_locked(Lock::acquire(LockMode::Exclusive, false)?)
+    }
+}
+
+impl NukeCommand {
+    /// Everything but the lock files is removed while holding `lock`, so another process taking
+    /// the lock before the home is gone finds nothing left to work with.
+    pub fn exec_locked(&self, lock: Lock) -> Result<(), ScoopieError> {
         let scoopie_home = env::var("SCOOPIE_HOME").map_err(|_| ScoopieError::EnvResolve)?;
-        PathBuf::from(scoopie_home).rm()?;
-        Pwsh::remove("SCOOPIE_HOME")?;
+        let scoopie_home = PathBuf::from(scoopie_home);
+        let store = user_env(&scoopie_home);
+
+        // Undo what installed apps added to the environment while their receipts are still around.
+        Receipt::installed()
+            .unwrap_or_default()
+            .iter()
+            .try_for_each(|receipt| receipt.env.revert(store.as_ref()))?;
+
+        store.remove_from_path(&[scoopie_home.join("shims")])?;
+        store.remove("SCOOPIE_HOME")?;
+
+        fs::read_dir(&scoopie_home)
+            .map_err(|_| ScoopieError::FailedToReadFile(scoopie_home.clone()))?
+            .filter_map(Result::ok)
+            .filter(|entry| {
+                !["scoopie.lock", "scoopie.lock.json"]
+                    .contains(&entry.file_name().to_str().unwrap_or_default())
+            })
+            .try_for_each(|entry| entry.path().rm())?;
+
+        drop(lock);
+        scoopie_home.rm()?;
 
         Ok(())
     }
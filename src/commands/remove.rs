@@ -1,6 +1,11 @@
 use argh::FromArgs;
 
 use super::prelude::*;
+use crate::core::{
+    buckets::Buckets,
+    download::Cache,
+    install::{script_runner, uninstall, Receipt},
+};
 use crate::error::ScoopieError;
 
 #[derive(FromArgs, PartialEq, Debug)]
@@ -15,12 +20,51 @@ pub struct RemoveCommand {
     all: bool,
 
     #[argh(switch, short = 'c')]
-    /// remove cached apps and repos
+    /// remove cached downloads of the app, with --all or without an app every cached download
+    /// and synced bucket (they're recreated on the next sync)
     cache: bool,
+
+    #[argh(switch, short = 'f')]
+    /// remove even if other installed apps depend on it
+    force: bool,
+
+    #[argh(switch, short = 'p')]
+    /// also remove persisted data (settings, user files) of the app
+    purge: bool,
+
+    #[argh(switch)]
+    /// skip manifest scripts (hooks and uninstallers)
+    no_scripts: bool,
 }
 
 impl ExecuteCommand for RemoveCommand {
     fn exec(&self) -> Result<(), ScoopieError> {
-        todo!()
+        let scripts = script_runner(self.no_scripts);
+
+        match (&self.app, self.all) {
+            (_, true) => Receipt::installed()?.iter().try_for_each(|receipt| {
+                uninstall(&receipt.app, true, self.purge, scripts.as_ref())
+            })?,
+            (Some(app), false) => uninstall(app, self.force, self.purge, scripts.as_ref())?,
+            (None, false) if self.cache => {}
+            (None, false) => eprintln!("App argument required"),
+        };
+
+        if self.cache {
+            let app = match self.all {
+                true => None,
+                false => self.app.as_deref(),
+            };
+
+            let purged = Cache::purge(app)?;
+            println!("Removed {purged} cached download(s)");
+
+            if app.is_none() {
+                Buckets::clear()?;
+                println!("Removed all synced buckets");
+            }
+        }
+
+        Ok(())
     }
 }
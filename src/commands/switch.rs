@@ -0,0 +1,22 @@
+use argh::FromArgs;
+
+use super::prelude::*;
+use crate::core::install::switch;
+use crate::error::ScoopieError;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Switch an app to another installed version, e.g. "scoopie switch python@3.11.4"
+#[argh(subcommand, name = "switch")]
+pub struct SwitchCommand {
+    #[argh(positional)]
+    app: String,
+}
+
+impl ExecuteCommand for SwitchCommand {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        match self.app.split_once('@') {
+            Some((app, version)) => switch(app, version),
+            None => Err(ScoopieError::VersionRequired(self.app.clone())),
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use argh::FromArgs;
+
+use super::prelude::*;
+use crate::core::install::{script_runner, update, update_all};
+use crate::error::ScoopieError;
+
+#[derive(FromArgs, PartialEq, Debug)]
+/// Update specified app or all installed apps to the latest version in the buckets
+#[argh(subcommand, name = "update")]
+pub struct UpdateCommand {
+    #[argh(positional)]
+    app: Option<String>,
+
+    #[argh(switch)]
+    /// skip manifest scripts (hooks and installers)
+    no_scripts: bool,
+}
+
+impl UpdateCommand {
+    pub fn update(app: Option<&str>, no_scripts: bool) -> Result<(), ScoopieError> {
+        let scripts = script_runner(no_scripts);
+
+        match app {
+            Some(app) => println!("{}", update(app, scripts.as_ref())?),
+            None => update_all(scripts.as_ref())?
+                .iter()
+                .for_each(|status| match status {
+                    Ok(status) => println!("{status}"),
+                    Err(e) => eprintln!("Error: {e}"),
+                }),
+        };
+
+        Ok(())
+    }
+}
+
+impl ExecuteCommand for UpdateCommand {
+    fn exec(&self) -> Result<(), ScoopieError> {
+        Self::update(self.app.as_deref(), self.no_scripts)
+    }
+}
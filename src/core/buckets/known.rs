@@ -0,0 +1,29 @@
+/// Scoop's official bucket catalogue, these can be added by name alone.
+pub const KNOWN_BUCKETS: [(&str, &str); 10] = [
+    ("main", "https://github.com/ScoopInstaller/Main"),
+    ("extras", "https://github.com/ScoopInstaller/Extras"),
+    ("versions", "https://github.com/ScoopInstaller/Versions"),
+    ("nirsoft", "https://github.com/ScoopInstaller/Nirsoft"),
+    (
+        "sysinternals",
+        "https://github.com/niheaven/scoop-sysinternals",
+    ),
+    ("php", "https://github.com/ScoopInstaller/PHP"),
+    (
+        "nerd-fonts",
+        "https://github.com/matthewjberger/scoop-nerd-fonts",
+    ),
+    (
+        "nonportable",
+        "https://github.com/ScoopInstaller/Nonportable",
+    ),
+    ("java", "https://github.com/ScoopInstaller/Java"),
+    ("games", "https://github.com/Calinou/scoop-games"),
+];
+
+pub fn known_bucket_url(name: &str) -> Option<&'static str> {
+    KNOWN_BUCKETS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, url)| *url)
+}
@@ -0,0 +1,125 @@
+use std::{fs, path::PathBuf};
+
+use super::metadata::{MetaData, MetaDataEntry};
+use super::sync::{SyncAll, SyncStatus};
+use super::{BucketName, Buckets};
+
+use crate::core::config::*;
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+impl Buckets {
+    /// Adds the bucket `name` to the config and syncs it right away, nothing is kept if the
+    /// sync fails.
+    pub fn add(name: &str, url: &str) -> Result<SyncStatus, ScoopieError> {
+        Self::validate_name(name)?;
+
+        let mut config = Config::read()?;
+        config.add_bucket(name, url)?;
+
+        match Self::sync_bucket(name, url) {
+            Ok(status) => {
+                config.save()?;
+                Ok(status)
+            }
+            Err(err) => {
+                let _ = Self::remove_files(name);
+                Err(err)
+            }
+        }
+    }
+
+    /// Removes the bucket `name` from the config along with everything synced for it.
+    pub fn remove(name: &str) -> Result<(), ScoopieError> {
+        let mut config = Config::read()?;
+        config.remove_bucket(name)?;
+        config.save()?;
+
+        Self::remove_files(name)
+    }
+
+    /// Renames the bucket `from` to `to`. `retarget` runs once the rename is known to be possible
+    /// but before anything is changed, so that whatever refers to the bucket by name can follow.
+    pub fn rename(
+        from: &str,
+        to: &str,
+        retarget: impl FnOnce() -> Result<(), ScoopieError>,
+    ) -> Result<(), ScoopieError> {
+        Self::validate_name(to)?;
+
+        let mut config = Config::read()?;
+        config.rename_bucket(from, to)?;
+
+        retarget()?;
+        MetaData::rename(from, to)?;
+
+        let (old, new) = (Self::paths(from)?, Self::paths(to)?);
+
+        old.into_iter()
+            .zip(new)
+            .filter(|(old, _)| old.exists())
+            .try_for_each(|(old, new)| {
+                fs::rename(&old, &new).map_err(|_| ScoopieError::FailedToMove(old, new))
+            })?;
+
+        config.save()
+    }
+
+    /// Every configured bucket along with what its last sync recorded, highest priority first.
+    pub fn summary() -> Result<Vec<(BucketName, String, MetaDataEntry)>, ScoopieError> {
+        let metadata = MetaData::read()?;
+        let config = Config::read()?;
+        let names = config.list_buckets();
+        let mut urls = config.known_buckets();
+
+        Ok(names
+            .into_iter()
+            .map(|name| {
+                let url = urls.remove(&name).unwrap_or_default();
+                let entry = metadata.get(&name);
+                (name, url, entry)
+            })
+            .collect())
+    }
+
+    /// Makes `buckets` the ones looked into first, in that order.
+    pub fn prioritize(buckets: &[String]) -> Result<(), ScoopieError> {
+        let mut config = Config::read()?;
+        config.set_priority(buckets)?;
+        config.save()
+    }
+
+    /// Bucket names end up as file names under `buckets/`, so they can't point anywhere else.
+    fn validate_name(name: &str) -> Result<(), ScoopieError> {
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name != "metadata.json"
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+        match valid {
+            true => Ok(()),
+            false => Err(ScoopieError::InvalidBucketName(name.into())),
+        }
+    }
+
+    fn remove_files(name: &str) -> Result<(), ScoopieError> {
+        Self::paths(name)?
+            .into_iter()
+            .filter(|path| path.exists())
+            .try_for_each(|path| path.rm())?;
+
+        MetaData::remove(name)
+    }
+
+    /// The synced bucket file and the git mirror it's read from.
+    fn paths(name: &str) -> Result<[PathBuf; 2], ScoopieError> {
+        let buckets_dir = Config::buckets_dir()?;
+
+        Ok([
+            buckets_dir.join(name),
+            buckets_dir.join(".mirrors").join(name),
+        ])
+    }
+}
@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::{Path, PathBuf};
 
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{json, Value};
 use url::Url;
 
+use super::Version;
 use crate::core::config::*;
 use crate::core::download::{deserialize_hash, Hash};
 use crate::error::ScoopieError;
@@ -13,36 +15,48 @@ use crate::error::ScoopieError;
 /// This strictly follows Scoop's convention for app manifests, which could be found at: https://github.com/ScoopInstaller/Scoop/wiki/App-Manifests
 pub struct Manifest {
     // Required Properties
-    pub version: String,
+    pub version: Version,
     pub description: String,
     pub homepage: String,
     pub license: Value,
     // Optional Properties
-    bin: Option<Value>,
-    extract_dir: Option<Value>,
+    #[serde(default, deserialize_with = "deserialize_bin")]
+    bin: Option<Vec<Bin>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    extract_dir: Option<Vec<String>>,
     #[serde(rename = "##")]
     comments: Option<Value>,
     architecture: Option<Architecture>,
     autoupdate: Option<Value>, // It is used by scoop to check for autoupdates, currrently out-of-scope for Scoopie.
     checkver: Option<Value>, // It is used by scoop to check for updated versions, currrently out-of-scope for Scoopie.
-    depends: Option<Value>,
-    suggest: Option<Value>,
-    env_add_path: Option<Value>,
-    env_set: Option<HashMap<String, String>>,
-    extract_to: Option<Value>,
+    #[serde(default, deserialize_with = "deserialize_depends")]
+    depends: Option<Vec<Dependency>>,
+    #[serde(default, deserialize_with = "deserialize_suggest")]
+    suggest: Option<BTreeMap<String, Vec<Dependency>>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    env_add_path: Option<Vec<String>>,
+    env_set: Option<BTreeMap<String, String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    extract_to: Option<Vec<String>>,
     #[serde(default, deserialize_with = "deserialize_hash")]
     hash: Option<Vec<Hash>>,
     innosetup: Option<bool>,
-    installer: Option<Value>, // TODO: implement it as individual struct so that it contains related properties.
-    notes: Option<Value>,
-    persist: Option<Value>,
-    post_install: Option<Value>,
-    post_uninstall: Option<Value>,
-    pre_install: Option<Value>,
-    pre_uninstall: Option<Value>,
+    installer: Option<Installer>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    notes: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_persist")]
+    persist: Option<Vec<Persist>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    post_install: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    post_uninstall: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    pre_install: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    pre_uninstall: Option<Vec<String>>,
     psmodule: Option<HashMap<String, String>>,
     shortcuts: Option<Vec<Vec<String>>>,
-    uninstaller: Option<Value>, // TODO: Same options as installer, but the file/script is run to uninstall the application.
+    uninstaller: Option<Installer>,
     #[serde(default, deserialize_with = "deserialize_url")]
     url: Option<Vec<Url>>,
     // Undocumented Properties
@@ -65,7 +79,7 @@ where
         Some(Value::Array(arr)) => arr
             .iter()
             .map(|url| match url {
-                Value::String(s) => Url::parse(&s).map_err(serde::de::Error::custom),
+                Value::String(s) => Url::parse(s).map_err(serde::de::Error::custom),
                 _ => Err(serde::de::Error::custom("Invalid Url Format")),
             })
             .collect::<Result<Vec<_>, _>>()
@@ -74,9 +88,304 @@ where
     }
 }
 
-impl ToString for Manifest {
-    fn to_string(&self) -> String {
-        json!(self).to_string()
+/// Scoop allows most list-like properties to be either a single string or an array of strings.
+fn deserialize_str_or_vec<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<Value> = Deserialize::deserialize(deserializer)?;
+
+    match value {
+        Some(Value::String(s)) => Ok(Some(vec![s])),
+        Some(Value::Array(arr)) => arr
+            .into_iter()
+            .map(|v| match v {
+                Value::String(s) => Ok(s),
+                _ => Err(serde::de::Error::custom("Expected an array of strings")),
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// An executable exposed through `shims`, written in manifests as `path` or `[path, alias, args...]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bin {
+    pub path: String,
+    pub alias: String,
+    pub args: Vec<String>,
+}
+
+impl Bin {
+    fn new(path: String, alias: Option<String>, args: Vec<String>) -> Self {
+        let alias = match alias {
+            Some(alias) if !alias.is_empty() => alias,
+            _ => Path::new(&path.replace('\\', "/"))
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+        };
+
+        Self { path, alias, args }
+    }
+}
+
+impl Serialize for Bin {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut entry = vec![&self.path, &self.alias];
+        entry.extend(&self.args);
+        entry.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bin {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: Value = Deserialize::deserialize(deserializer)?;
+
+        match value {
+            Value::String(path) => Ok(Bin::new(path, None, vec![])),
+            Value::Array(entry) => {
+                let mut entry = entry.into_iter().map(|v| match v {
+                    Value::String(s) => Ok(s),
+                    _ => Err(serde::de::Error::custom("Invalid bin entry")),
+                });
+
+                let path = entry
+                    .next()
+                    .ok_or_else(|| serde::de::Error::custom("Empty bin entry"))??;
+                let alias = entry.next().transpose()?;
+                let args = entry.collect::<Result<Vec<_>, _>>()?;
+
+                Ok(Bin::new(path, alias, args))
+            }
+            _ => Err(serde::de::Error::custom("Invalid bin entry")),
+        }
+    }
+}
+
+fn deserialize_bin<'de, D>(deserializer: D) -> Result<Option<Vec<Bin>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<Value> = Deserialize::deserialize(deserializer)?;
+
+    match value {
+        Some(Value::String(path)) => Ok(Some(vec![Bin::new(path, None, vec![])])),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .map(|entry| Bin::deserialize(entry).map_err(serde::de::Error::custom))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Data kept in `persists/<app>/` across updates, written in manifests as `source` or `[source, target]`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Persist {
+    pub source: String,
+    pub target: String,
+}
+
+impl Persist {
+    fn new(source: String, target: Option<String>) -> Self {
+        let target = match target {
+            Some(target) if !target.is_empty() => target,
+            _ => source.clone(),
+        };
+
+        Self { source, target }
+    }
+}
+
+impl Serialize for Persist {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        vec![&self.source, &self.target].serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Persist {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: Value = Deserialize::deserialize(deserializer)?;
+
+        match value {
+            Value::String(source) => Ok(Persist::new(source, None)),
+            Value::Array(entry) => {
+                let mut entry = entry.into_iter().map(|v| match v {
+                    Value::String(s) => Ok(s),
+                    _ => Err(serde::de::Error::custom("Invalid persist entry")),
+                });
+
+                let source = entry
+                    .next()
+                    .ok_or_else(|| serde::de::Error::custom("Empty persist entry"))??;
+                let target = entry.next().transpose()?;
+
+                Ok(Persist::new(source, target))
+            }
+            _ => Err(serde::de::Error::custom("Invalid persist entry")),
+        }
+    }
+}
+
+fn deserialize_persist<'de, D>(deserializer: D) -> Result<Option<Vec<Persist>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<Value> = Deserialize::deserialize(deserializer)?;
+
+    match value {
+        Some(Value::String(source)) => Ok(Some(vec![Persist::new(source, None)])),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .map(|entry| Persist::deserialize(entry).map_err(serde::de::Error::custom))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// How to run an app's (un)installer, either an executable shipped with the app or a script.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Installer {
+    pub file: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    pub script: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    pub args: Option<Vec<String>>,
+    #[serde(default)]
+    pub keep: bool,
+}
+
+impl Installer {
+    /// `args` with `$dir` and `$version` replaced.
+    pub fn args(&self, dir: &Path, version: &Version) -> Vec<String> {
+        self.args
+            .iter()
+            .flatten()
+            .map(|arg| {
+                arg.replace("$dir", &dir.to_string_lossy())
+                    .replace("$version", version.as_str())
+            })
+            .collect()
+    }
+
+    pub fn script(&self) -> Vec<String> {
+        self.script.clone().unwrap_or_default()
+    }
+}
+
+/// An app required by another one, written in manifests either as `app` or `bucket/app`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dependency {
+    pub bucket: Option<String>,
+    pub app: String,
+}
+
+impl From<&str> for Dependency {
+    fn from(value: &str) -> Self {
+        let value = value.trim().to_lowercase();
+
+        match value.split_once('/') {
+            Some((bucket, app)) => Self {
+                bucket: Some(bucket.into()),
+                app: app.into(),
+            },
+            None => Self {
+                bucket: None,
+                app: value,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Dependency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.bucket {
+            Some(bucket) => write!(f, "{bucket}/{}", self.app),
+            None => write!(f, "{}", self.app),
+        }
+    }
+}
+
+impl Serialize for Dependency {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+fn deserialize_depends<'de, D>(deserializer: D) -> Result<Option<Vec<Dependency>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let depends = deserialize_str_or_vec(deserializer)?;
+    Ok(depends.map(|depends| {
+        depends
+            .iter()
+            .map(|d| Dependency::from(d.as_str()))
+            .collect()
+    }))
+}
+
+/// `suggest` maps a feature name to the apps that could provide it, each written as `app` or `[app, ...]`.
+fn deserialize_suggest<'de, D>(
+    deserializer: D,
+) -> Result<Option<BTreeMap<String, Vec<Dependency>>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value: Option<BTreeMap<String, Value>> = Deserialize::deserialize(deserializer)?;
+
+    value
+        .map(|suggest| {
+            suggest
+                .into_iter()
+                .map(|(feature, alternatives)| {
+                    let alternatives = match alternatives {
+                        Value::String(s) => vec![s],
+                        Value::Array(arr) => arr
+                            .into_iter()
+                            .map(|v| match v {
+                                Value::String(s) => Ok(s),
+                                _ => Err(serde::de::Error::custom("Expected an array of strings")),
+                            })
+                            .collect::<Result<Vec<_>, _>>()?,
+                        _ => vec![],
+                    };
+
+                    Ok((
+                        feature,
+                        alternatives
+                            .iter()
+                            .map(|a| Dependency::from(a.as_str()))
+                            .collect(),
+                    ))
+                })
+                .collect()
+        })
+        .transpose()
+}
+
+impl fmt::Display for Manifest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", json!(self))
     }
 }
 
@@ -91,6 +400,14 @@ impl TryFrom<PathBuf> for Manifest {
     }
 }
 
+impl TryFrom<&[u8]> for Manifest {
+    type Error = ScoopieError;
+
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        serde_json::from_slice::<Manifest>(value).map_err(|_| ScoopieError::InvalidManifestInBucket)
+    }
+}
+
 impl Manifest {
     pub fn url(&self) -> Vec<Url> {
         match &self.architecture {
@@ -105,6 +422,130 @@ impl Manifest {
             None => self.hash.clone().unwrap_or_default(),
         }
     }
+
+    /// Per-url subdirectory of the archive that should become the root of the app.
+    pub fn extract_dir(&self) -> Vec<String> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().extract_dir)
+            .or_else(|| self.extract_dir.clone())
+            .unwrap_or_default()
+    }
+
+    /// Per-url subdirectory of the app into which the archive should be extracted.
+    pub fn extract_to(&self) -> Vec<String> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().extract_to)
+            .or_else(|| self.extract_to.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn bin(&self) -> Vec<Bin> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().bin)
+            .or_else(|| self.bin.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn shortcuts(&self) -> Vec<Vec<String>> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().shortcuts)
+            .or_else(|| self.shortcuts.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn depends(&self) -> Vec<Dependency> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().depends)
+            .or_else(|| self.depends.clone())
+            .unwrap_or_default()
+    }
+
+    /// Features the app works better with, mapped to the apps that could provide them.
+    pub fn suggest(&self) -> BTreeMap<String, Vec<Dependency>> {
+        self.suggest.clone().unwrap_or_default()
+    }
+
+    /// Directories (relative to the app) that should be added to the user's `PATH`.
+    pub fn env_add_path(&self) -> Vec<String> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().env_add_path)
+            .or_else(|| self.env_add_path.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn env_set(&self) -> BTreeMap<String, String> {
+        self.env_set.clone().unwrap_or_default()
+    }
+
+    /// Script lines run after extraction, before the app is made current.
+    pub fn pre_install(&self) -> Vec<String> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().pre_install)
+            .or_else(|| self.pre_install.clone())
+            .unwrap_or_default()
+    }
+
+    /// Script lines run once the app has been made current.
+    pub fn post_install(&self) -> Vec<String> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().post_install)
+            .or_else(|| self.post_install.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn pre_uninstall(&self) -> Vec<String> {
+        self.pre_uninstall.clone().unwrap_or_default()
+    }
+
+    pub fn post_uninstall(&self) -> Vec<String> {
+        self.post_uninstall.clone().unwrap_or_default()
+    }
+
+    pub fn installer(&self) -> Option<Installer> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().installer)
+            .or_else(|| self.installer.clone())
+    }
+
+    pub fn uninstaller(&self) -> Option<Installer> {
+        self.architecture
+            .as_ref()
+            .and_then(|arch| arch.get().uninstaller)
+            .or_else(|| self.uninstaller.clone())
+    }
+
+    pub fn persist(&self) -> Vec<Persist> {
+        self.persist.clone().unwrap_or_default()
+    }
+
+    pub fn notes(&self) -> Vec<String> {
+        self.notes.clone().unwrap_or_default()
+    }
+
+    pub fn license(&self) -> String {
+        match &self.license {
+            Value::String(license) => license.clone(),
+            Value::Object(license) => license
+                .get("identifier")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            _ => String::new(),
+        }
+    }
+
+    pub fn innosetup(&self) -> bool {
+        self.innosetup.unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
@@ -136,10 +577,23 @@ struct Attrs {
     url: Option<Vec<Url>>,
     #[serde(default, deserialize_with = "deserialize_hash")]
     hash: Option<Vec<Hash>>,
-    extract_dir: Option<Value>,
-    bin: Option<Value>,
-    shortcuts: Option<Value>,
-    env_add_path: Option<Value>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    extract_dir: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    extract_to: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_bin")]
+    bin: Option<Vec<Bin>>,
+    #[serde(default, deserialize_with = "deserialize_depends")]
+    depends: Option<Vec<Dependency>>,
+    shortcuts: Option<Vec<Vec<String>>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    env_add_path: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    pre_install: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "deserialize_str_or_vec")]
+    post_install: Option<Vec<String>>,
+    installer: Option<Installer>,
+    uninstaller: Option<Installer>,
 }
 
 impl Attrs {
@@ -151,3 +605,74 @@ impl Attrs {
         self.hash.unwrap_or_default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_manifest;
+
+    fn bin(path: &str, alias: &str, args: &[&str]) -> Bin {
+        Bin {
+            path: path.into(),
+            alias: alias.into(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn bin_forms() {
+        let bins = |fields: &str| test_manifest(fields).bin();
+
+        assert_eq!(bins(""), vec![]);
+        assert_eq!(
+            bins(r#""bin": "bin\\foo.exe""#),
+            vec![bin("bin\\foo.exe", "foo", &[])]
+        );
+        assert_eq!(
+            bins(r#""bin": ["foo.exe", "bar.cmd"]"#),
+            vec![bin("foo.exe", "foo", &[]), bin("bar.cmd", "bar", &[])]
+        );
+        assert_eq!(
+            bins(r#""bin": [["foo.exe", "f", "--a", "b c"], ["bar.exe"], ["baz.exe", ""]]"#),
+            vec![
+                bin("foo.exe", "f", &["--a", "b c"]),
+                bin("bar.exe", "bar", &[]),
+                bin("baz.exe", "baz", &[]),
+            ]
+        );
+        assert_eq!(
+            bins(r#""bin": ["foo.exe", ["foo.exe", "foo-dev", "--dev"]]"#),
+            vec![
+                bin("foo.exe", "foo", &[]),
+                bin("foo.exe", "foo-dev", &["--dev"])
+            ]
+        );
+
+        assert!(Manifest::try_from(
+            br#"{"version": "1", "description": "", "homepage": "", "license": "", "bin": [[]]}"#
+                .as_slice()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn bin_per_architecture() {
+        let manifest = test_manifest(
+            r#""bin": "foo.exe",
+            "architecture": {
+                "64bit": { "bin": "foo64.exe" },
+                "32bit": { "bin": ["foo32.exe"] },
+                "arm64": { "url": "https://example.com/foo.zip" }
+            }"#,
+        );
+
+        let expected = match Config::arch().unwrap() {
+            Arch::Bit64 => bin("foo64.exe", "foo64", &[]),
+            Arch::Bit32 => bin("foo32.exe", "foo32", &[]),
+            // Without its own `bin`, an architecture falls back to the top-level one.
+            Arch::Arm64 => bin("foo.exe", "foo", &[]),
+        };
+
+        assert_eq!(manifest.bin(), vec![expected]);
+    }
+}
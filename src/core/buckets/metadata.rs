@@ -1,51 +1,104 @@
 use std::{
     collections::HashMap,
-    fs::{self, OpenOptions},
-    io::Write,
+    fs,
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
 };
 
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 
-use crate::{core::config::*, error::*};
+use crate::{core::config::*, error::*, utils::*};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct MetaData(HashMap<String, MetaDataEntry>);
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct MetaDataEntry {
-    source: String,
+    pub source: String,
     pub commit_id: String,
+    #[serde(default)]
+    pub apps: Option<usize>,
+    #[serde(default)]
+    pub synced_at: u64,
 }
 
-impl<'a> MetaData {
+impl MetaData {
     pub fn read() -> Result<Self, ScoopieError> {
         let metadata_path = Config::buckets_dir()?.join("metadata.json");
-        let content = fs::read_to_string(metadata_path).unwrap();
-        Ok(serde_json::from_str(&content).unwrap())
+        let content = fs::read_to_string(&metadata_path)
+            .map_err(|_| ScoopieError::FailedToReadFile(metadata_path.clone()))?;
+
+        serde_json::from_str(&content).map_err(|_| ScoopieError::InvalidMetadata(metadata_path))
+    }
+
+    pub fn write(
+        &mut self,
+        name: &str,
+        url: &str,
+        commit_id: &str,
+        apps: usize,
+    ) -> Result<(), ScoopieError> {
+        let synced_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let entry = MetaDataEntry {
+            source: url.into(),
+            commit_id: commit_id.into(),
+            apps: Some(apps),
+            synced_at,
+        };
+
+        *self = Self::modify(|entries| {
+            entries.insert(name.into(), entry);
+        })?;
+
+        Ok(())
     }
 
-    pub fn write(&mut self, name: &str, url: &str, commit_id: &str) -> Result<(), ScoopieError> {
-        self.0.insert(
-            name.into(),
-            MetaDataEntry {
-                source: url.into(),
-                commit_id: commit_id.into(),
-            },
-        );
+    /// Forgets about the bucket `name`.
+    pub fn remove(name: &str) -> Result<(), ScoopieError> {
+        Self::modify(|entries| {
+            entries.remove(name);
+        })
+        .map(|_| ())
+    }
+
+    pub fn rename(from: &str, to: &str) -> Result<(), ScoopieError> {
+        Self::modify(|entries| {
+            if let Some(entry) = entries.remove(from) {
+                entries.insert(to.into(), entry);
+            }
+        })
+        .map(|_| ())
+    }
+
+    /// Applies `change` on top of what's on disk right now and writes it back.
+    fn modify(
+        change: impl FnOnce(&mut HashMap<String, MetaDataEntry>),
+    ) -> Result<Self, ScoopieError> {
+        // Buckets are synced in parallel, so merge with whatever the others wrote meanwhile.
+        static WRITING: Mutex<()> = Mutex::new(());
+        let _writing = WRITING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
 
         let metadata_path = Config::buckets_dir()?.join("metadata.json");
-        let mut file = OpenOptions::new()
-            .write(true)
-            .truncate(true)
-            .open(metadata_path)
-            .unwrap();
 
-        let content = json!(self.0).to_string();
+        // Missing means nothing was synced yet, but a corrupt file is reported rather than wiped.
+        let mut metadata = match metadata_path.exists() {
+            true => Self::read()?,
+            false => Self::default(),
+        };
+        change(&mut metadata.0);
 
-        file.write_all(content.as_bytes()).unwrap();
+        let content = json!(metadata.0).to_string();
 
-        Ok(())
+        metadata_path.write_atomic(content.as_bytes())?;
+
+        Ok(metadata)
     }
 
     pub fn get(&self, name: &str) -> MetaDataEntry {
@@ -53,18 +106,9 @@ impl<'a> MetaData {
     }
 }
 
-pub fn write_default_metadata<'a>() -> Result<(), ScoopieError> {
+pub fn write_default_metadata() -> Result<(), ScoopieError> {
     let metadata_path = Config::buckets_dir()?.join("metadata.json");
-    let mut file = OpenOptions::new()
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(metadata_path)
-        .unwrap();
-
     let content = json!(MetaData::default()).to_string();
 
-    file.write_all(content.as_bytes()).unwrap();
-
-    Ok(())
+    metadata_path.write_atomic(content.as_bytes())
 }
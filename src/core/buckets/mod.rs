@@ -1,37 +1,103 @@
+mod known;
+mod manage;
 mod manifest;
 mod metadata;
 mod query;
 mod sync;
+mod version;
 
-pub use manifest::Manifest;
-pub use metadata::write_default_metadata;
+pub use known::*;
+pub use manifest::{Bin, Dependency, Installer, Manifest, Persist};
+pub use metadata::{write_default_metadata, MetaData};
 pub use query::*;
 pub use sync::*;
+pub use version::Version;
 
-use std::{collections::HashMap, fmt, format, write};
+use std::{collections::HashMap, fmt, format, fs, write};
 
 use console::style;
 use serde::{Deserialize, Serialize};
 
+use crate::core::config::*;
+use crate::error::ScoopieError;
+use crate::utils::*;
+
 pub type AppName = String;
 #[derive(Debug, Deserialize, Clone, Serialize)]
 pub struct Bucket(HashMap<AppName, Manifest>);
 
 pub type BucketName = String;
+/// Buckets in priority order, see `Config::list_buckets`.
 #[derive(Debug, Deserialize, Clone, Serialize)]
-pub struct Buckets(HashMap<BucketName, Bucket>);
+pub struct Buckets(Vec<(BucketName, Bucket)>);
 
 impl Buckets {
     pub fn get_app_from(&self, app_name: &str, bucket_name: &str) -> Option<Manifest> {
-        self.0.get(bucket_name)?.0.get(app_name).cloned()
+        self.0
+            .iter()
+            .find(|(name, _)| name == bucket_name)
+            .and_then(|(_, bucket)| bucket.0.get(app_name).cloned())
     }
 
     pub fn get_app(&self, app_name: &str) -> Option<Manifest> {
+        self.find_app(app_name).map(|(_, manifest)| manifest)
+    }
+
+    /// Every bucket which ships `app_name`, highest priority first.
+    pub fn find_all(&self, app_name: &str) -> Vec<(BucketName, Manifest)> {
         self.0
-            .values()
-            .flat_map(|bucket| bucket.0.get(app_name))
-            .next()
-            .cloned()
+            .iter()
+            .filter_map(|(bucket_name, bucket)| {
+                Some((bucket_name.clone(), bucket.0.get(app_name)?.clone()))
+            })
+            .collect()
+    }
+
+    /// Same as `get_app`, but also tells which bucket the manifest came from.
+    pub fn find_app(&self, app_name: &str) -> Option<(BucketName, Manifest)> {
+        self.0.iter().find_map(|(bucket_name, bucket)| {
+            Some((bucket_name.clone(), bucket.0.get(app_name)?.clone()))
+        })
+    }
+
+    /// Same as `find_app`, but warns when other buckets ship `app_name` as well.
+    pub fn resolve_app(&self, app_name: &str) -> Option<(BucketName, Manifest)> {
+        let mut found = self.find_all(app_name).into_iter();
+        let (bucket_name, manifest) = found.next()?;
+
+        let others = found
+            .map(|(other, manifest)| format!("{other}/{app_name} (v{})", manifest.version))
+            .collect::<Vec<_>>();
+
+        if !others.is_empty() {
+            eprintln!(
+                "{}",
+                style(format!(
+                    "Using {bucket_name}/{app_name} (v{}), also found {}. Hint: Use \"<bucket>/{app_name}\" to pick another or change the bucket priority with \"scoopie bucket priority\"",
+                    manifest.version,
+                    others.join(", ")
+                ))
+                .yellow()
+            );
+        }
+
+        Some((bucket_name, manifest))
+    }
+}
+
+impl Buckets {
+    /// Drops every synced bucket along with its metadata, they are recreated on the next sync.
+    pub fn clear() -> Result<(), ScoopieError> {
+        let buckets_dir = Config::buckets_dir()?;
+
+        fs::read_dir(&buckets_dir)
+            .map_err(|_| ScoopieError::FailedToReadFile(buckets_dir.clone()))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.file_name().is_some_and(|name| name != "metadata.json"))
+            .try_for_each(|path| path.rm())?;
+
+        write_default_metadata()
     }
 }
 
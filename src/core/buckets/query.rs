@@ -1,4 +1,3 @@
-use std::collections::HashMap;
 use std::fs::read_to_string;
 
 use super::{Bucket, Buckets};
@@ -48,7 +47,7 @@ impl Query<&str> for Buckets {
         let buckets = buckets
             .into_par_iter()
             .map(predicate)
-            .collect::<Result<HashMap<_, _>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Buckets(buckets))
     }
@@ -66,7 +65,7 @@ impl Query<&str> for Buckets {
             let bucket: Bucket = from_str(&content)
                 .map_err(|_| ScoopieError::FailedToReadBucket(bucket_name.to_string()))?;
 
-            let bucket: Bucket = bucket.query_app(&query)?;
+            let bucket: Bucket = bucket.query_app(query)?;
 
             Ok((bucket_name, bucket))
         };
@@ -74,16 +73,44 @@ impl Query<&str> for Buckets {
         let bucket = buckets
             .into_par_iter()
             .map(predicate)
-            .collect::<Result<HashMap<_, _>, _>>()?;
+            .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Buckets(bucket))
     }
 }
 
+impl Buckets {
+    /// Looks up several apps at once, reading every bucket only a single time.
+    pub fn query_apps(apps: &[String]) -> Result<Self, ScoopieError> {
+        let buckets_dir = Config::buckets_dir()?;
+        let buckets = Config::read()?.list_buckets();
+
+        let predicate = |bucket_name: String| -> Result<(String, Bucket), ScoopieError> {
+            let bucket_path = buckets_dir.join(&bucket_name);
+
+            let content = read_to_string(&bucket_path)
+                .map_err(|_| ScoopieError::FailedToReadFile(bucket_path))?;
+
+            let bucket: Bucket = from_str(&content)
+                .map_err(|_| ScoopieError::FailedToReadBucket(bucket_name.to_string()))?;
+
+            Ok((bucket_name, bucket.query_apps(apps)))
+        };
+
+        let buckets = buckets
+            .into_par_iter()
+            .map(predicate)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Buckets(buckets))
+    }
+}
+
 trait QueryBucket<T>: Sized {
     type Error;
     fn query_fts(self, pat: &str) -> Result<Self, Self::Error>;
     fn query_app(self, app: &str) -> Result<Self, Self::Error>;
+    fn query_apps(self, apps: &[String]) -> Self;
 }
 
 impl QueryBucket<&str> for Bucket {
@@ -96,7 +123,7 @@ impl QueryBucket<&str> for Bucket {
             self.0
                 .into_par_iter()
                 .filter(|(app_name, manifest)| {
-                    re.is_match(&app_name) || re.is_match(&manifest.description)
+                    re.is_match(app_name) || re.is_match(&manifest.description)
                 })
                 .collect(),
         ))
@@ -110,4 +137,13 @@ impl QueryBucket<&str> for Bucket {
                 .collect(),
         ))
     }
+
+    fn query_apps(self, apps: &[String]) -> Self {
+        Bucket(
+            self.0
+                .into_par_iter()
+                .filter(|(app_name, _)| apps.contains(app_name))
+                .collect(),
+        )
+    }
 }
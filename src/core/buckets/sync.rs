@@ -1,10 +1,7 @@
 use std::{
-    collections::HashMap,
-    ffi::OsStr,
     fmt::{self, Display, Formatter},
-    fs::{self, OpenOptions},
-    io::Write,
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
     time::Duration,
 };
 
@@ -17,10 +14,12 @@ use super::metadata::MetaData;
 use super::{Bucket, Buckets};
 
 use console::style;
-use git2::{build::RepoBuilder, FetchOptions};
-use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use git2::{Commit, Delta, Direction, FetchOptions, ObjectType, Oid, Remote, Repository, Tree};
+use indicatif::{MultiProgress, ProgressBar};
 use rayon::prelude::*;
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use url::Url;
 
 #[derive(Debug, PartialEq, PartialOrd)]
 pub enum SyncStatus {
@@ -40,23 +39,17 @@ impl Display for SyncStatus {
 }
 
 impl Bucket {
-    fn write_to(&self, path: &PathBuf) {
+    fn write_to(&self, path: &PathBuf) -> Result<(), ScoopieError> {
         let json = json!(self.0).to_string();
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .truncate(true)
-            .open(path)
-            .unwrap();
-
-        file.write_all(json.as_bytes()).unwrap();
+        path.write_atomic(json.as_bytes())
     }
 }
 
 pub trait SyncAll {
     type Error;
     fn sync() -> Result<Vec<SyncStatus>, Self::Error>;
+    fn sync_bucket(name: &str, url: &str) -> Result<SyncStatus, Self::Error>;
 }
 
 impl SyncAll for Buckets {
@@ -70,6 +63,48 @@ impl SyncAll for Buckets {
             .map(|v| Bucket::sync(&mb, v.0, v.1))
             .collect()
     }
+
+    fn sync_bucket(name: &str, url: &str) -> Result<SyncStatus, Self::Error> {
+        Bucket::sync(&MultiProgress::new(), name, url)
+    }
+}
+
+impl Buckets {
+    /// The manifest file of `app` exactly as `bucket` has it, for git buckets as of the synced commit.
+    pub fn raw_manifest(bucket: &str, app: &str) -> Result<Vec<u8>, ScoopieError> {
+        let synced = MetaData::read()?.get(bucket);
+        let file = format!("{app}.json");
+        let missing = || ScoopieError::NoAppFoundInBucket(app.into(), bucket.into());
+
+        match Source::of(&synced.source) {
+            Source::Dir(dir) => {
+                let path = match Layout::of_dir(&dir) {
+                    Layout::Bucket => dir.join("bucket").join(file),
+                    Layout::Root => dir.join(file),
+                };
+
+                fs::read(path).map_err(|_| missing())
+            }
+            Source::Git => {
+                let mirror_dir = Config::buckets_dir()?.join(".mirrors").join(bucket);
+                let repo = Repository::open(mirror_dir).map_err(|_| missing())?;
+                let tree = Oid::from_str(&synced.commit_id)
+                    .and_then(|oid| repo.find_commit(oid))
+                    .and_then(|commit| commit.tree())
+                    .map_err(|_| missing())?;
+
+                let path = match Layout::of_tree(&tree) {
+                    Layout::Bucket => Path::new("bucket").join(file),
+                    Layout::Root => PathBuf::from(file),
+                };
+
+                tree.get_path(&path)
+                    .and_then(|entry| repo.find_blob(entry.id()))
+                    .map(|blob| blob.content().to_vec())
+                    .map_err(|_| missing())
+            }
+        }
+    }
 }
 
 trait Sync: ReadFromRepo {
@@ -86,140 +121,571 @@ impl Sync for Bucket {
         name: &str,
         url: &str,
     ) -> Result<SyncStatus, <Self as Sync>::Error> {
-        let temp_dir_builder = TempDir::build()?;
-        let temp_dir = temp_dir_builder.path();
-
         let pb = mb.add(ProgressBar::new_spinner());
         pb.enable_steady_tick(Duration::from_millis(3));
 
         pb.set_message(
-            style(format!("Fetching bucket {name} from remote..."))
+            style(format!("Checking bucket {name} for changes..."))
                 .bold()
                 .to_string(),
         );
 
-        let mut fo = FetchOptions::new();
-        fo.depth(1);
+        let buckets_dir = Config::buckets_dir()?;
+        let bucket_path = buckets_dir.join(name);
 
-        let repo = RepoBuilder::new()
-            .bare(true)
-            .fetch_options(fo)
-            .clone(url, &temp_dir)
-            .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+        let mut metadata = MetaData::read()?;
+        let synced = metadata.get(name);
 
-        let head = repo.head().map_err(|_| ScoopieError::SyncUnableToGetHead)?;
+        // Without the bucket file there's nothing the synced commit could stand for.
+        let synced_commit = match bucket_path.exists() {
+            true => synced.commit_id.as_str(),
+            false => "",
+        };
 
-        let commit_id = head
-            .peel_to_commit()
-            .map_err(|_| ScoopieError::SyncUnableToGetCommit)?
-            .id()
-            .to_string();
+        let fetched = match Source::of(url) {
+            Source::Dir(dir) => Self::from_dir(&pb, name, &dir, synced_commit)?,
+            Source::Git => {
+                let mirror_dir = buckets_dir.join(".mirrors").join(name);
+                Self::from_git(&pb, name, url, &mirror_dir, &bucket_path, synced_commit)?
+            }
+        };
 
-        let bucket_dir = Config::buckets_dir()?;
-        let bucket_path = bucket_dir.join(name);
+        let Some((bucket, commit_id)) = fetched else {
+            // Still counts as a sync, buckets synced by older versions get their app count here.
+            let apps = match synced.apps {
+                Some(apps) => apps,
+                None => Bucket::open(&bucket_path)?.0.len(),
+            };
+            metadata.write(name, url, &synced.commit_id, apps)?;
+
+            pb.finish_with_message(
+                style(format!("Bucket: {name} is already synced to the remote."))
+                    .bold()
+                    .to_string(),
+            );
+            return Ok(SyncStatus::UpToDate(name.into()));
+        };
 
         pb.set_message(
-            style(format!("Reading metadata for bucket {name}..."))
+            style(format!("Writing metadata for bucket {name}..."))
                 .bold()
                 .to_string(),
         );
 
-        let mut metadata = MetaData::read()?;
+        let created = !bucket_path.exists();
 
-        let st = match (
-            bucket_path.exists(),
-            metadata.get(name).commit_id == commit_id,
-        ) {
-            (true, true) => {
+        bucket.write_to(&bucket_path)?;
+        metadata.write(name, url, &commit_id, bucket.0.len())?;
+
+        match created {
+            true => {
                 pb.finish_with_message(
-                    style(format!("Bucket: {name} is already synced to the remote."))
-                        .bold()
-                        .to_string(),
+                    style(format!(
+                        "Bucket: {name} is created and synced to the remote."
+                    ))
+                    .bold()
+                    .to_string(),
                 );
-                SyncStatus::UpToDate(name.into())
+                Ok(SyncStatus::Created(name.into()))
             }
-            (true, false) => {
-                pb.set_message(
-                    style(format!("Fetching manifests from bucket {name}..."))
-                        .bold()
-                        .to_string(),
-                );
-                Self::read(&temp_dir)?.write_to(&bucket_path);
-                pb.set_message(
-                    style(format!("Writing metadata for bucket {name}..."))
-                        .bold()
-                        .to_string(),
-                );
-                metadata.write(name, url, &commit_id)?;
+            false => {
                 pb.finish_with_message(
                     style(format!("Bucket: {name} is now synced to the remote."))
                         .bold()
                         .to_string(),
                 );
-                SyncStatus::Synced(name.into())
+                Ok(SyncStatus::Synced(name.into()))
             }
+        }
+    }
+}
 
-            (false, _) => {
-                pb.set_message(
-                    style(format!("Fetching manifests from bucket {name}..."))
-                        .bold()
-                        .to_string(),
-                );
-                Self::read(&temp_dir)?.write_to(&bucket_path);
-                pb.set_message(
-                    style(format!("Writing metadata for bucket {name}..."))
-                        .bold()
-                        .to_string(),
-                );
-                metadata.write(name, url, &commit_id)?;
-                pb.finish_with_message(
-                    style(format!(
-                        "Bucket: {name} is created and synced to the remote."
-                    ))
-                    .bold()
+/// Where a bucket is synced from.
+enum Source {
+    /// A git repository, remote or local (a path or a `file://` url).
+    Git,
+    /// A plain directory of manifests, with no git involved.
+    Dir(PathBuf),
+}
+
+impl Source {
+    fn of(url: &str) -> Self {
+        // Checked by scheme first, as Windows paths like `C:\bucket` parse as urls too.
+        let path = match Url::parse(url) {
+            Ok(url) if url.scheme() == "file" => url.to_file_path().ok(),
+            _ => Some(PathBuf::from(url)).filter(|path| path.exists()),
+        };
+
+        match path {
+            Some(path) if path.is_dir() && Repository::open(&path).is_err() => Source::Dir(path),
+            _ => Source::Git,
+        }
+    }
+}
+
+impl Bucket {
+    /// Manifests at the remote's HEAD along with its commit id, `None` if that's still `synced_commit`.
+    fn from_git(
+        pb: &ProgressBar,
+        name: &str,
+        url: &str,
+        mirror_dir: &Path,
+        bucket_path: &Path,
+        synced_commit: &str,
+    ) -> Result<Option<(Self, String)>, ScoopieError> {
+        let repo = Self::mirror(mirror_dir, url)?;
+        let mut remote = repo
+            .find_remote("origin")
+            .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+
+        let head = Self::remote_head(&mut remote)?;
+
+        if head.to_string() == synced_commit {
+            return Ok(None);
+        }
+
+        pb.set_message(
+            style(format!("Fetching bucket {name} from remote..."))
+                .bold()
+                .to_string(),
+        );
+
+        let mut fo = FetchOptions::new();
+        fo.depth(1);
+
+        remote
+            .fetch(&["HEAD"], Some(&mut fo), None)
+            .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+
+        let commit = repo
+            .find_commit(head)
+            .map_err(|_| ScoopieError::SyncUnableToGetCommit)?;
+
+        pb.set_message(
+            style(format!("Reading manifests from bucket {name}..."))
+                .bold()
+                .to_string(),
+        );
+
+        // Only manifests touched since the last sync need parsing, given both are at hand.
+        let previous = Oid::from_str(synced_commit)
+            .and_then(|oid| repo.find_commit(oid))
+            .ok()
+            .zip(Bucket::open(bucket_path).ok());
+
+        let bucket = match previous {
+            Some((previous, bucket)) => bucket.update(&repo, &previous, &commit)?,
+            None => Self::read(&repo, &commit)?,
+        };
+
+        Ok(Some((bucket, head.to_string())))
+    }
+
+    /// Manifests of a plain directory along with a digest of them, `None` if that's still
+    /// `synced_commit`.
+    fn from_dir(
+        pb: &ProgressBar,
+        name: &str,
+        dir: &Path,
+        synced_commit: &str,
+    ) -> Result<Option<(Self, String)>, ScoopieError> {
+        let layout = Layout::of_dir(dir);
+
+        let manifests_dir = match layout {
+            Layout::Bucket => dir.join("bucket"),
+            Layout::Root => dir.to_path_buf(),
+        };
+
+        let mut files = fs::read_dir(&manifests_dir)
+            .map_err(|_| ScoopieError::BucketsNotFound)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter_map(|path| {
+                let app = path
+                    .file_name()?
+                    .to_str()?
+                    .strip_suffix(".json")?
+                    .to_string();
+                Some((app, path))
+            })
+            .map(|(app, path)| match fs::read(&path) {
+                Ok(content) => Ok((app, content)),
+                Err(_) => Err(ScoopieError::FailedToReadFile(path)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        files.sort();
+
+        // There's no commit to go by, so a digest of every manifest stands in for one.
+        let mut hasher = Sha256::new();
+        files.iter().for_each(|(app, content)| {
+            hasher.update(format!("{app}\0{}\0", content.len()));
+            hasher.update(content);
+        });
+        let digest = hex::encode(hasher.finalize());
+
+        if digest == synced_commit {
+            return Ok(None);
+        }
+
+        pb.set_message(
+            style(format!("Reading manifests from bucket {name}..."))
+                .bold()
+                .to_string(),
+        );
+
+        Ok(Some((Self::parse(layout, files)?, digest)))
+    }
+
+    /// Opens the persistent bare clone of a bucket kept under `buckets/.mirrors/`, creating it if needed.
+    fn mirror(dir: &Path, url: &str) -> Result<Repository, ScoopieError> {
+        let repo = match dir.exists() {
+            true => Repository::open(dir),
+            false => Repository::init_bare(dir),
+        }
+        .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+
+        // The bucket may have been pointed at a different url since it was last synced.
+        match repo.find_remote("origin") {
+            Ok(remote) if remote.url() == Some(url) => {}
+            Ok(_) => repo
+                .remote_set_url("origin", url)
+                .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?,
+            Err(_) => {
+                repo.remote("origin", url)
+                    .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+            }
+        }
+
+        Ok(repo)
+    }
+
+    /// Commit the remote's HEAD points at, without fetching anything.
+    fn remote_head(remote: &mut Remote) -> Result<Oid, ScoopieError> {
+        remote
+            .connect(Direction::Fetch)
+            .map_err(|_| ScoopieError::SyncUnableToFetchRepo)?;
+
+        let head = remote
+            .list()
+            .map_err(|_| ScoopieError::SyncUnableToGetHead)?
+            .iter()
+            .find(|head| head.name() == "HEAD")
+            .map(|head| head.oid())
+            .ok_or(ScoopieError::SyncUnableToGetHead);
+
+        let _ = remote.disconnect();
+
+        head
+    }
+
+    /// Previously synced bucket file.
+    fn open(path: &Path) -> Result<Self, ScoopieError> {
+        let content =
+            fs::read_to_string(path).map_err(|_| ScoopieError::FailedToReadFile(path.into()))?;
+
+        serde_json::from_str(&content).map_err(|_| {
+            ScoopieError::FailedToReadBucket(
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
                     .to_string(),
-                );
-                SyncStatus::Created(name.into())
+            )
+        })
+    }
+
+    /// Applies the manifests changed between `from` and `to` on top of `self`.
+    fn update(
+        mut self,
+        repo: &Repository,
+        from: &Commit,
+        to: &Commit,
+    ) -> Result<Self, ScoopieError> {
+        let (from, to) = from
+            .tree()
+            .and_then(|from| Ok((from, to.tree()?)))
+            .map_err(|_| ScoopieError::SyncUnableToGetCommit)?;
+
+        let layout = Layout::of_tree(&to);
+
+        // Moving manifests around (e.g. into `bucket/`) changes every path, start over then.
+        if layout != Layout::of_tree(&from) {
+            return Self::from_tree(repo, &to);
+        }
+
+        let diff = repo
+            .diff_tree_to_tree(Some(&from), Some(&to), None)
+            .map_err(|_| ScoopieError::SyncUnableToGetCommit)?;
+
+        diff.deltas().try_for_each(|delta| {
+            if let Some(app) = delta.old_file().path().and_then(|path| layout.app(path)) {
+                self.0.remove(&app);
             }
+
+            let app = match (delta.status(), delta.new_file().path()) {
+                (Delta::Deleted, _) | (_, None) => None,
+                (_, Some(path)) => layout.app(path),
+            };
+
+            let Some(app) = app else {
+                return Ok(());
+            };
+
+            let blob = repo
+                .find_blob(delta.new_file().id())
+                .map_err(|_| ScoopieError::SyncUnableToGetCommit)?;
+
+            if let Some(manifest) = layout.parse(blob.content())? {
+                self.0.insert(app, manifest);
+            }
+
+            Ok(())
+        })?;
+
+        Ok(self)
+    }
+
+    /// Parses every manifest of `tree`, straight from the object database.
+    fn from_tree(repo: &Repository, tree: &Tree) -> Result<Self, ScoopieError> {
+        let layout = Layout::of_tree(tree);
+
+        let dir = match layout {
+            Layout::Bucket => tree
+                .get_name("bucket")
+                .and_then(|entry| entry.to_object(repo).ok())
+                .and_then(|object| object.into_tree().ok())
+                .ok_or(ScoopieError::BucketsNotFound)?,
+            Layout::Root => tree.clone(),
+        };
+
+        // Blobs borrow the (non thread-safe) repository, so they're copied out before parsing.
+        let blobs = dir
+            .iter()
+            .filter(|entry| entry.kind() == Some(ObjectType::Blob))
+            .filter_map(|entry| {
+                let app = entry.name()?.strip_suffix(".json")?.to_string();
+                let blob = repo.find_blob(entry.id()).ok()?;
+
+                Some((app, blob.content().to_vec()))
+            })
+            .collect::<Vec<_>>();
+
+        Self::parse(layout, blobs)
+    }
+
+    /// Parses manifest contents keyed by app name, in parallel.
+    fn parse(layout: Layout, manifests: Vec<(String, Vec<u8>)>) -> Result<Self, ScoopieError> {
+        let manifests = manifests
+            .into_par_iter()
+            .map(|(app, content)| Ok(layout.parse(&content)?.map(|manifest| (app, manifest))))
+            .collect::<Result<Vec<_>, ScoopieError>>()?;
+
+        Ok(Bucket(manifests.into_iter().flatten().collect()))
+    }
+}
+
+/// Where a bucket keeps its manifests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Layout {
+    /// `bucket/<app>.json`, what Scoop's own buckets use.
+    Bucket,
+    /// `<app>.json` at the repository root, used by some community buckets.
+    Root,
+}
+
+impl Layout {
+    fn of_tree(tree: &Tree) -> Self {
+        match tree.get_name("bucket").and_then(|entry| entry.kind()) {
+            Some(ObjectType::Tree) => Layout::Bucket,
+            _ => Layout::Root,
+        }
+    }
+
+    fn of_dir(dir: &Path) -> Self {
+        match dir.join("bucket").is_dir() {
+            true => Layout::Bucket,
+            false => Layout::Root,
+        }
+    }
+
+    /// App name of the manifest at `path` (relative to the repository root), `None` for other files.
+    fn app(&self, path: &Path) -> Option<String> {
+        let dir = match self {
+            Layout::Bucket => Path::new("bucket"),
+            Layout::Root => Path::new(""),
         };
 
-        Ok(st)
+        match (path.parent(), path.extension()) {
+            (Some(parent), Some(ext)) if parent == dir && ext == "json" => {
+                Some(path.file_stem()?.to_string_lossy().to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Root level buckets share the root with other JSON files (e.g. `renovate.json`), those are
+    /// skipped rather than failing the whole bucket.
+    fn parse(&self, content: &[u8]) -> Result<Option<Manifest>, ScoopieError> {
+        match (self, Manifest::try_from(content)) {
+            (_, Ok(manifest)) => Ok(Some(manifest)),
+            (Layout::Root, Err(_)) => Ok(None),
+            (Layout::Bucket, Err(err)) => Err(err),
+        }
     }
 }
 
 trait ReadFromRepo: Sized {
     type Error;
-    fn read(path: &PathBuf) -> Result<Self, Self::Error>;
+    fn read(repo: &Repository, commit: &Commit) -> Result<Self, Self::Error>;
 }
 
 impl ReadFromRepo for Bucket {
     type Error = ScoopieError;
 
-    fn read(path: &PathBuf) -> Result<Self, Self::Error> {
-        let bucket_path = path.join("bucket");
-
-        match (bucket_path.is_dir(), bucket_path.exists()) {
-            (true, true) => {
-                let manifests = fs::read_dir(bucket_path)
-                    .map_err(|_| ScoopieError::BucketsNotFound)?
-                    .filter_map(Result::ok)
-                    .filter(|entry| entry.path().extension() == Some(OsStr::new("json")))
-                    .par_bridge()
-                    .map(|entry| -> Result<(String, Manifest), ScoopieError> {
-                        let file_path = entry.path();
-                        let app_name = file_path
-                            .file_stem()
-                            .unwrap_or_default()
-                            .to_string_lossy()
-                            .to_string();
-
-                        let manifest = Manifest::try_from(file_path)?;
-                        Ok((app_name, manifest))
-                    })
-                    .collect::<Result<HashMap<_, _>, _>>()?;
-                Ok(Bucket(manifests))
-            }
-            _ => Err(ScoopieError::BucketsNotFound),
-        }
+    fn read(repo: &Repository, commit: &Commit) -> Result<Self, Self::Error> {
+        let tree = commit
+            .tree()
+            .map_err(|_| ScoopieError::SyncUnableToGetCommit)?;
+
+        Self::from_tree(repo, &tree)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use git2::Signature;
+
+    use super::*;
+    use crate::utils::test_dir;
+
+    fn manifest(version: &str) -> String {
+        format!(
+            r#"{{"version": "{version}", "description": "", "homepage": "", "license": "MIT"}}"#
+        )
+    }
+
+    fn version(bucket: &Bucket, app: &str) -> Option<String> {
+        bucket
+            .0
+            .get(app)
+            .map(|manifest| manifest.version.to_string())
+    }
+
+    /// Commits `apps` (name and version) as the whole of `bucket/` on top of HEAD.
+    fn commit(repo: &Repository, apps: &[(&str, &str)]) -> Oid {
+        let mut manifests = repo.treebuilder(None).unwrap();
+
+        apps.iter().for_each(|(app, version)| {
+            let blob = repo.blob(manifest(version).as_bytes()).unwrap();
+            manifests
+                .insert(format!("{app}.json"), blob, 0o100644)
+                .unwrap();
+        });
+
+        let mut root = repo.treebuilder(None).unwrap();
+        root.insert("bucket", manifests.write().unwrap(), 0o040000)
+            .unwrap();
+        let tree = repo.find_tree(root.write().unwrap()).unwrap();
+
+        let signature = Signature::now("scoopie", "scoopie@example.com").unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Update",
+            &tree,
+            &parent.iter().collect::<Vec<_>>(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sources() {
+        let dir = test_dir("sync-sources");
+        let plain = dir.join("plain");
+        let bare = dir.join("bare.git");
+        PathBuf::create(&plain).unwrap();
+        Repository::init_bare(&bare).unwrap();
+
+        let url = |path: &Path| Url::from_file_path(path).unwrap().to_string();
+
+        assert!(matches!(Source::of(&plain.to_string_lossy()), Source::Dir(path) if path == plain));
+        assert!(matches!(Source::of(&url(&plain)), Source::Dir(path) if path == plain));
+        assert!(matches!(Source::of(&bare.to_string_lossy()), Source::Git));
+        assert!(matches!(Source::of(&url(&bare)), Source::Git));
+        assert!(matches!(
+            Source::of("https://github.com/ScoopInstaller/Main"),
+            Source::Git
+        ));
+    }
+
+    #[test]
+    fn directories_sync_when_changed() {
+        let dir = test_dir("sync-dir");
+        let manifests = dir.join("bucket");
+        PathBuf::create(&manifests).unwrap();
+        fs::write(manifests.join("foo.json"), manifest("1.0")).unwrap();
+        fs::write(manifests.join("bar.json"), manifest("2.0")).unwrap();
+
+        let pb = ProgressBar::hidden();
+        let (bucket, digest) = Bucket::from_dir(&pb, "dir", &dir, "").unwrap().unwrap();
+
+        assert_eq!(version(&bucket, "foo").as_deref(), Some("1.0"));
+        assert_eq!(version(&bucket, "bar").as_deref(), Some("2.0"));
+        assert!(Bucket::from_dir(&pb, "dir", &dir, &digest)
+            .unwrap()
+            .is_none());
+
+        fs::write(manifests.join("foo.json"), manifest("1.1")).unwrap();
+        let (bucket, changed) = Bucket::from_dir(&pb, "dir", &dir, &digest)
+            .unwrap()
+            .unwrap();
+
+        assert_ne!(changed, digest);
+        assert_eq!(version(&bucket, "foo").as_deref(), Some("1.1"));
+    }
+
+    #[test]
+    fn git_buckets_apply_changes() {
+        let dir = test_dir("sync-git");
+        let remote = dir.join("remote.git");
+        let mirror_dir = dir.join("mirror");
+        let bucket_path = dir.join("git");
+
+        let repo = Repository::init_bare(&remote).unwrap();
+        let first = commit(&repo, &[("foo", "1.0"), ("bar", "1.0")]);
+        let url = Url::from_file_path(&remote).unwrap().to_string();
+
+        let pb = ProgressBar::hidden();
+        let sync = |synced: &str| {
+            Bucket::from_git(&pb, "git", &url, &mirror_dir, &bucket_path, synced).unwrap()
+        };
+
+        let (bucket, head) = sync("").unwrap();
+        assert_eq!(head, first.to_string());
+        assert_eq!(version(&bucket, "foo").as_deref(), Some("1.0"));
+        assert_eq!(version(&bucket, "bar").as_deref(), Some("1.0"));
+
+        assert!(sync(&head).is_none());
+
+        // Only what the diff touches is parsed again, everything else comes from the bucket file.
+        let mut synced = bucket;
+        synced.0.insert(
+            String::from("kept"),
+            Manifest::try_from(manifest("3.0").as_bytes()).unwrap(),
+        );
+        synced.write_to(&bucket_path).unwrap();
+
+        let second = commit(&repo, &[("foo", "1.1"), ("baz", "1.0")]);
+        let (bucket, head) = sync(&head).unwrap();
+
+        assert_eq!(head, second.to_string());
+        assert_eq!(version(&bucket, "foo").as_deref(), Some("1.1"));
+        assert_eq!(version(&bucket, "baz").as_deref(), Some("1.0"));
+        assert_eq!(version(&bucket, "bar"), None);
+        assert_eq!(version(&bucket, "kept").as_deref(), Some("3.0"));
     }
 }
@@ -0,0 +1,317 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Scoop-style app version, e.g. `2.42.0`, `1.2.3-beta.2`, `1.1.1w`, `2023-08-01`, `nightly`.
+///
+/// Versions are compared segment by segment where runs of digits are compared numerically,
+/// pre-release tags (`alpha`, `beta`, `rc`, `a1`, `b2`, ...) sort below the release they precede,
+/// a bare letter after a number (`1.1.1w`) is a patch letter sorting above it and build metadata
+/// (after `+`) is only used to break ties, by the same rules. `nightly` is newer than anything else.
+#[derive(Debug, Clone)]
+pub struct Version(String);
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    // Declared lowest first, `1.0-beta` < `1.0w` < `1.0.1` for the same position.
+    Pre(u8),
+    Tag(String),
+    Num(u64),
+}
+
+impl Segment {
+    /// Rank of well-known pre-release tags, `a`/`b` only count when a number follows them
+    /// (`1.0a1`), otherwise they're patch letters (`1.1.1a`).
+    fn pre_release(tag: &str, numbered: bool) -> Option<u8> {
+        match tag {
+            "dev" | "snapshot" => Some(0),
+            "alpha" => Some(1),
+            "a" if numbered => Some(1),
+            "beta" => Some(2),
+            "b" if numbered => Some(2),
+            "pre" | "preview" => Some(3),
+            "rc" => Some(4),
+            _ => None,
+        }
+    }
+
+    /// Splits `part` into runs of digits and runs of letters.
+    fn tokens(part: &str) -> Vec<String> {
+        let mut tokens = vec![];
+        let mut token = String::new();
+
+        for c in part.chars() {
+            match c {
+                '.' | '-' | '_' | ' ' => tokens.push(std::mem::take(&mut token)),
+                c if c.is_alphanumeric() => {
+                    let boundary = token
+                        .chars()
+                        .last()
+                        .is_some_and(|last| last.is_ascii_digit() != c.is_ascii_digit());
+
+                    if boundary {
+                        tokens.push(std::mem::take(&mut token));
+                    }
+
+                    token.push(c);
+                }
+                _ => {}
+            }
+        }
+
+        tokens.push(token);
+        tokens.retain(|token| !token.is_empty());
+        tokens
+    }
+
+    fn parse(part: &str) -> Vec<Segment> {
+        let tokens = Self::tokens(&part.to_lowercase());
+
+        tokens
+            .iter()
+            .enumerate()
+            .map(|(idx, token)| match token.parse::<u64>() {
+                Ok(num) => Segment::Num(num),
+                Err(_) => {
+                    let numbered = tokens
+                        .get(idx + 1)
+                        .is_some_and(|next| next.parse::<u64>().is_ok());
+
+                    match Segment::pre_release(token, numbered) {
+                        Some(rank) => Segment::Pre(rank),
+                        None => Segment::Tag(token.clone()),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    fn compare(a: &[Segment], b: &[Segment]) -> Ordering {
+        for idx in 0..a.len().max(b.len()) {
+            let ord = match (a.get(idx), b.get(idx)) {
+                (Some(x), Some(y)) => x.cmp(y),
+                // `1.0` is `1.0.0`, `1.0-beta` is older than `1.0` and `1.1.1w` is newer than `1.1.1`.
+                (Some(x), None) => match x {
+                    Segment::Num(num) => num.cmp(&0),
+                    Segment::Pre(_) => Ordering::Less,
+                    Segment::Tag(_) => Ordering::Greater,
+                },
+                (None, Some(y)) => match y {
+                    Segment::Num(num) => 0.cmp(num),
+                    Segment::Pre(_) => Ordering::Greater,
+                    Segment::Tag(_) => Ordering::Less,
+                },
+                (None, None) => Ordering::Equal,
+            };
+
+            if ord != Ordering::Equal {
+                return ord;
+            }
+        }
+
+        Ordering::Equal
+    }
+}
+
+impl Version {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_nightly(&self) -> bool {
+        self.0.to_lowercase().starts_with("nightly")
+    }
+
+    /// The version an install of this is recorded as. A bare `nightly` means "whatever is latest
+    /// right now", so it's stamped with today's date (`nightly-20230801`) to tell installs apart.
+    pub fn installed(&self) -> Version {
+        match self.is_undated() {
+            true => Version(format!("nightly-{}", today())),
+            false => self.clone(),
+        }
+    }
+
+    fn is_undated(&self) -> bool {
+        self.0.eq_ignore_ascii_case("nightly")
+    }
+
+    fn release(&self) -> &str {
+        self.0.split_once('+').map_or(self.0.as_str(), |(v, _)| v)
+    }
+
+    fn build(&self) -> &str {
+        self.0.split_once('+').map_or("", |(_, b)| b)
+    }
+
+    fn segments(&self) -> Vec<Segment> {
+        let release = self.release().to_lowercase();
+
+        match release.strip_prefix('v') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => Segment::parse(rest),
+            _ => Segment::parse(&release),
+        }
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.is_nightly(), other.is_nightly()) {
+            (true, false) => return Ordering::Greater,
+            (false, true) => return Ordering::Less,
+            // An undated `nightly` is always ahead of one installed earlier.
+            (true, true) => match (self.is_undated(), other.is_undated()) {
+                (true, true) => return Ordering::Equal,
+                (true, false) => return Ordering::Greater,
+                (false, true) => return Ordering::Less,
+                (false, false) => {}
+            },
+            (false, false) => {}
+        }
+
+        Segment::compare(&self.segments(), &other.segments()).then_with(|| {
+            Segment::compare(
+                &Segment::parse(self.build()),
+                &Segment::parse(other.build()),
+            )
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+impl From<&str> for Version {
+    fn from(value: &str) -> Self {
+        Self(value.trim().to_string())
+    }
+}
+
+impl Display for Version {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let version: String = Deserialize::deserialize(deserializer)?;
+        Ok(Version::from(version.as_str()))
+    }
+}
+
+/// Today's UTC date as `YYYYMMDD`.
+fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+        / 86400;
+
+    // Days since 1970-01-01 to a civil date, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}{month:02}{day:02}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version: &str) -> Version {
+        Version::from(version)
+    }
+
+    #[test]
+    fn numeric_segments() {
+        assert!(v("2.42.0") > v("2.9.1"));
+        assert!(v("1.10") > v("1.9"));
+        assert!(v("v1.2.0") == v("1.2.0"));
+        assert!(v("2023-08-01") > v("2023-07-31"));
+    }
+
+    #[test]
+    fn missing_segments_are_zero() {
+        assert_eq!(v("1.0").cmp(&v("1.0.0")), Ordering::Equal);
+        assert!(v("1.0.1") > v("1.0"));
+    }
+
+    #[test]
+    fn patch_letters() {
+        // OpenSSL style.
+        assert!(v("1.1.1a") > v("1.1.1"));
+        assert!(v("1.1.1b") > v("1.1.1a"));
+        assert!(v("1.1.1w") > v("1.1.1c"));
+        assert!(v("1.1.2") > v("1.1.1w"));
+    }
+
+    #[test]
+    fn pre_releases() {
+        assert!(v("1.0-alpha") < v("1.0-beta"));
+        assert!(v("1.0-beta") < v("1.0-rc1"));
+        assert!(v("1.0-rc1") < v("1.0-rc2"));
+        assert!(v("1.0rc") < v("1.0"));
+        assert!(v("1.2.3-beta.2") > v("1.2.3-beta.1"));
+        assert!(v("1.2.3-beta.2") < v("1.2.3"));
+        assert!(v("3.12.0a1") < v("3.12.0b1"));
+        assert!(v("3.12.0b4") < v("3.12.0"));
+    }
+
+    #[test]
+    fn build_metadata_breaks_ties() {
+        assert!(v("1.0.0+2") > v("1.0.0+1"));
+        assert!(v("1.0.0+1") > v("1.0.0"));
+        assert!(v("1.0.1+1") > v("1.0.0+9"));
+        assert!(v("1.0+10") > v("1.0+9"));
+        assert!(v("1.0+build.100") > v("1.0+build.20"));
+        assert!(v("1.0+20230801.2") < v("1.0+20230801.10"));
+        assert_eq!(v("1.0+1").cmp(&v("1.0+1.0")), Ordering::Equal);
+    }
+
+    #[test]
+    fn nightly() {
+        assert!(v("nightly") > v("99.0"));
+        assert!(v("nightly") > v("nightly-20230801"));
+        assert!(v("nightly-20230802") > v("nightly-20230801"));
+        assert_eq!(v("nightly").cmp(&v("nightly")), Ordering::Equal);
+
+        let installed = v("nightly").installed();
+        assert!(installed.as_str().starts_with("nightly-2"));
+        assert_eq!(installed.as_str().len(), "nightly-YYYYMMDD".len());
+        assert_eq!(v("1.0").installed().as_str(), "1.0");
+    }
+}
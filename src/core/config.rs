@@ -1,11 +1,11 @@
 use std::{
     collections::HashMap,
-    env, fs,
+    env, fmt, fs,
     path::{Path, PathBuf},
 };
 
 use crate::error::*;
-use crate::utils::Pwsh;
+use crate::utils::{AtomicWrite, Pwsh};
 
 use serde::{Deserialize, Serialize};
 
@@ -17,6 +17,10 @@ pub trait Reader: Sized {
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Config {
     buckets: HashMap<String, String>,
+    /// Buckets looked into first when an app is in several of them, in this order. Buckets not
+    /// listed come after, by name.
+    #[serde(default)]
+    priority: Vec<String>,
     download: Download,
 }
 
@@ -37,10 +41,12 @@ impl Default for Config {
             ),
         ];
 
+        let priority = buckets.iter().map(|(name, _)| name.clone()).collect();
         let buckets = HashMap::from(buckets);
 
         Self {
             buckets,
+            priority,
             download: Default::default(),
         }
     }
@@ -100,8 +106,7 @@ impl Reader for Config {
     type Error = ScoopieError;
 
     fn read() -> Result<Self, Self::Error> {
-        let home_dir = Pwsh::home_dir()?;
-        let scoopie_config = home_dir.join(".config\\scoopie.json");
+        let scoopie_config = Self::path()?;
 
         match scoopie_config.exists() {
             true => Config::try_from(scoopie_config),
@@ -111,16 +116,94 @@ impl Reader for Config {
 }
 
 impl Config {
-    pub fn known_buckets(self) -> HashMap<String, String> {
-        self.buckets
+    fn path() -> Result<PathBuf, ScoopieError> {
+        Ok(Pwsh::home_dir()?.join(".config\\scoopie.json"))
+    }
+
+    /// Writes the config back to where `Config::read` found it.
+    pub fn save(&self) -> Result<(), ScoopieError> {
+        let config = serde_json::to_string_pretty(self).map_err(|_| ScoopieError::InvalidConfig)?;
+
+        Self::path()?.write_atomic(config.as_bytes())
     }
 
-    pub fn list_buckets(self) -> Vec<String> {
-        self.buckets.into_keys().collect()
+    pub fn add_bucket(&mut self, name: &str, url: &str) -> Result<(), ScoopieError> {
+        match self.buckets.contains_key(name) {
+            true => Err(ScoopieError::BucketAlreadyExists(name.into())),
+            false => {
+                self.buckets.insert(name.into(), url.into());
+                Ok(())
+            }
+        }
     }
 
-    pub fn download(self) -> Download {
-        self.download
+    /// Removes the bucket `name`, returning its url.
+    pub fn remove_bucket(&mut self, name: &str) -> Result<String, ScoopieError> {
+        let url = self
+            .buckets
+            .remove(name)
+            .ok_or_else(|| ScoopieError::UnknownBucket(name.into()))?;
+
+        self.priority.retain(|bucket| bucket != name);
+
+        Ok(url)
+    }
+
+    pub fn rename_bucket(&mut self, from: &str, to: &str) -> Result<(), ScoopieError> {
+        if self.buckets.contains_key(to) {
+            return Err(ScoopieError::BucketAlreadyExists(to.into()));
+        }
+
+        let url = self
+            .buckets
+            .remove(from)
+            .ok_or_else(|| ScoopieError::UnknownBucket(from.into()))?;
+        self.buckets.insert(to.into(), url);
+
+        self.priority
+            .iter_mut()
+            .filter(|bucket| *bucket == from)
+            .for_each(|bucket| *bucket = to.into());
+
+        Ok(())
+    }
+
+    /// Puts `buckets` first, in that order, the others keep coming after them by name.
+    pub fn set_priority(&mut self, buckets: &[String]) -> Result<(), ScoopieError> {
+        if let Some(unknown) = buckets.iter().find(|b| !self.buckets.contains_key(*b)) {
+            return Err(ScoopieError::UnknownBucket(unknown.clone()));
+        }
+
+        self.priority = buckets.iter().fold(vec![], |mut priority, bucket| {
+            if !priority.contains(bucket) {
+                priority.push(bucket.clone());
+            }
+            priority
+        });
+
+        Ok(())
+    }
+
+    pub fn known_buckets(self) -> HashMap<String, String> {
+        self.buckets
+    }
+
+    /// Names of the configured buckets, highest priority first.
+    pub fn list_buckets(&self) -> Vec<String> {
+        let mut rest = self
+            .buckets
+            .keys()
+            .filter(|bucket| !self.priority.contains(bucket))
+            .cloned()
+            .collect::<Vec<_>>();
+        rest.sort();
+
+        self.priority
+            .iter()
+            .filter(|bucket| self.buckets.contains_key(*bucket))
+            .cloned()
+            .chain(rest)
+            .collect()
     }
 }
 
@@ -194,13 +277,26 @@ impl DefaultDirs for Config {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum Arch {
+    #[serde(rename = "64bit")]
     Bit64,
+    #[serde(rename = "32bit")]
     Bit32,
+    #[serde(rename = "arm64")]
     Arm64,
 }
 
+impl fmt::Display for Arch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Arch::Bit64 => write!(f, "64bit"),
+            Arch::Bit32 => write!(f, "32bit"),
+            Arch::Arm64 => write!(f, "arm64"),
+        }
+    }
+}
+
 pub trait Stats {
     fn arch() -> Result<Arch, ScoopieError>;
 }
@@ -0,0 +1,56 @@
+use std::{collections::BTreeMap, fs};
+
+use crate::core::config::*;
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+pub struct Cache;
+
+impl Cache {
+    /// Sizes of downloads looked up without downloading them, keyed by their cache file name.
+    const SIZES: &'static str = "sizes.json";
+
+    /// Removes cached downloads of `app` (named `app#version#...`), or the whole cache when no app is given.
+    pub fn purge(app: Option<&str>) -> Result<usize, ScoopieError> {
+        let cache_dir = Config::cache_dir()?;
+        let prefix = app.map(|app| format!("{}#", app.trim().to_lowercase()));
+
+        let files = fs::read_dir(&cache_dir)
+            .map_err(|_| ScoopieError::FailedToReadFile(cache_dir.clone()))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name() != Self::SIZES)
+            .filter(|entry| match &prefix {
+                Some(prefix) => entry.file_name().to_string_lossy().starts_with(prefix),
+                None => true,
+            })
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+
+        files.iter().try_for_each(|file| file.rm())?;
+
+        let sizes = cache_dir.join(Self::SIZES);
+
+        if prefix.is_none() && sizes.exists() {
+            sizes.rm()?;
+        }
+
+        Ok(files.len())
+    }
+
+    /// Download sizes known so far, anything unreadable is simply looked up again.
+    pub fn sizes() -> BTreeMap<String, u64> {
+        Config::cache_dir()
+            .ok()
+            .and_then(|cache_dir| fs::read(cache_dir.join(Self::SIZES)).ok())
+            .and_then(|content| serde_json::from_slice(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn remember_sizes(sizes: &BTreeMap<String, u64>) -> Result<(), ScoopieError> {
+        let path = Config::cache_dir()?.join(Self::SIZES);
+        let content = serde_json::to_vec_pretty(sizes)
+            .map_err(|_| ScoopieError::FailedToWriteFile(path.clone()))?;
+
+        path.write_atomic(&content)
+    }
+}
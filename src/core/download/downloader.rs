@@ -9,7 +9,7 @@ use console::style;
 use indicatif::{ProgressBar, ProgressStyle};
 use url::Url;
 
-use super::Hash;
+use super::{Cache, Hash};
 
 use {
     crate::comptime_regex,
@@ -18,6 +18,8 @@ use {
     crate::utils::*,
 };
 
+const HEAD_TIMEOUT_SECS: u64 = 10;
+
 #[derive(Debug)]
 pub enum DownloadStatus {
     Downloaded(String),
@@ -25,6 +27,16 @@ pub enum DownloadStatus {
     AlreadyInCache(String),
 }
 
+impl DownloadStatus {
+    pub fn file_name(&self) -> &str {
+        match self {
+            DownloadStatus::Downloaded(s) => s,
+            DownloadStatus::DownloadedAndVerified(s) => s,
+            DownloadStatus::AlreadyInCache(s) => s,
+        }
+    }
+}
+
 pub struct Downloader;
 
 impl Downloader {
@@ -33,29 +45,38 @@ impl Downloader {
 
         let (app_name, manifest) = match query.split_once('/') {
             Some((bucket, app)) => {
-                let manifest = Buckets::query_app(&app)?
-                    .get_app_from(&app, &bucket)
+                let manifest = Buckets::query_app(app)?
+                    .get_app_from(app, bucket)
                     .ok_or(ScoopieError::NoAppFoundInBucket(app.into(), bucket.into()))?;
 
                 (app, manifest)
             }
             None => {
                 let app_name = &query;
-                let manifest = Buckets::query_app(&app_name)?
-                    .get_app(&query)
+                let (_, manifest) = Buckets::query_app(app_name)?
+                    .resolve_app(&query)
                     .ok_or(ScoopieError::NoAppFound(app_name.into()))?;
 
                 (app_name.as_str(), manifest)
             }
         };
 
+        Self::fetch(app_name, &manifest, verify)
+    }
+
+    /// Downloads every url of an already resolved manifest, statuses are in the same order as `Manifest::url`.
+    pub fn fetch(
+        app_name: &str,
+        manifest: &Manifest,
+        verify: bool,
+    ) -> Result<Vec<DownloadStatus>, ScoopieError> {
         let version = &manifest.version;
         let urls = manifest.url();
         let hashes = manifest.hash();
 
         zip(urls, hashes)
             .map(|(url, hash)| {
-                let (pkg_name, file) = extract_names(&app_name, version, &url);
+                let (pkg_name, file) = extract_names(app_name, version.as_str(), &url);
                 let hash = if verify { Some(&hash) } else { None };
                 dwnld(&pkg_name, url.as_str(), &file, hash)
             })
@@ -63,6 +84,59 @@ impl Downloader {
     }
 }
 
+impl Downloader {
+    /// Size of every download of a manifest, taken from the cache when it's already downloaded
+    /// or was looked up before, and from a HEAD request otherwise.
+    pub fn sizes(app_name: &str, manifest: &Manifest) -> Result<Vec<Option<u64>>, ScoopieError> {
+        let cache_dir = Config::cache_dir()?;
+        let mut known = Cache::sizes();
+        let mut looked_up = false;
+
+        let sizes = manifest
+            .url()
+            .iter()
+            .map(|url| {
+                let (_, file) = extract_names(app_name, manifest.version.as_str(), url);
+
+                if let Ok(file_metadata) = metadata(cache_dir.join(&file)) {
+                    return Some(file_metadata.len());
+                }
+
+                if let Some(size) = known.get(&file) {
+                    return Some(*size);
+                }
+
+                let size = head_size(url.as_str())?;
+                known.insert(file, size);
+                looked_up = true;
+
+                Some(size)
+            })
+            .collect();
+
+        // Only spares a request next time, so failing to keep them isn't worth failing over.
+        if looked_up {
+            let _ = Cache::remember_sizes(&known);
+        }
+
+        Ok(sizes)
+    }
+}
+
+/// Size reported for `url` by the server, if it answers (in time) and tells at all.
+fn head_size(url: &str) -> Option<u64> {
+    let response = minreq::head(url)
+        .with_timeout(HEAD_TIMEOUT_SECS)
+        .send()
+        .ok()?;
+
+    if response.status_code < 200 || response.status_code > 299 {
+        return None;
+    }
+
+    response.headers.get("content-length")?.parse().ok()
+}
+
 fn dwnld(
     pkg_name: &str,
     url: &str,
@@ -77,7 +151,7 @@ fn dwnld(
         .send_lazy()
         .map_err(|_| ScoopieError::FailedToSendReq)?;
 
-    if response.status_code < 200 && response.status_code > 299 {
+    if response.status_code < 200 || response.status_code > 299 {
         return Err(ScoopieError::RequestFailed(
             file_name.into(),
             response.reason_phrase,
@@ -110,7 +184,11 @@ fn dwnld(
 
         let mut chunk = [0; 4096];
 
-        while let Ok(bytes_read) = response.read(&mut chunk) {
+        loop {
+            let bytes_read = response
+                .read(&mut chunk)
+                .map_err(|_| ScoopieError::UnableToGetChunk(pkg_name.into()))?;
+
             if bytes_read == 0 {
                 break;
             }
@@ -127,7 +205,10 @@ fn dwnld(
         match verify {
             Some(hash) => match hash.verify(&file_path)? {
                 true => Ok(DownloadStatus::DownloadedAndVerified(file_name.into())),
-                false => Err(ScoopieError::WrongDigest(file_name.into())),
+                // Left in the cache, it would pass for a complete download next time.
+                false => file_path
+                    .rm()
+                    .and(Err(ScoopieError::WrongDigest(file_name.into()))),
             },
             None => Ok(DownloadStatus::Downloaded(file_name.into())),
         }
@@ -135,9 +216,16 @@ fn dwnld(
 
     if file_path.exists() {
         let file_metadata = metadata(&file_path)
-            .map_err(|_| (ScoopieError::FailedToGetMetadata(file_path.to_path_buf())))?;
+            .map_err(|_| ScoopieError::FailedToGetMetadata(file_path.to_path_buf()))?;
+
+        // A matching size only tells the download is complete, not that it's the right file.
+        let cached = file_metadata.len() == total_size
+            && match verify {
+                Some(hash) => hash.verify(&file_path)?,
+                None => true,
+            };
 
-        match file_metadata.len() == total_size {
+        match cached {
             true => Ok(DownloadStatus::AlreadyInCache(file_name.into())),
             false => file_path.rm().and_then(|_| downloader()),
         }
@@ -148,7 +236,7 @@ fn dwnld(
 
 fn extract_names(app_name: &str, version: &str, url: &Url) -> (String, String) {
     let pkg_name = match url.path_segments() {
-        Some(segments) => segments.last().unwrap_or_default(),
+        Some(mut segments) => segments.next_back().unwrap_or_default(),
         None => "",
     }
     .to_lowercase();
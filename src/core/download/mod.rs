@@ -1,5 +1,7 @@
+mod cache;
 mod downloader;
 mod verify;
 
+pub use cache::Cache;
 pub use downloader::*;
 pub use verify::*;
@@ -19,7 +19,7 @@ impl Serialize for Hash {
         S: Serializer,
     {
         match self {
-            Hash::SHA256(digest) => serializer.serialize_str(&format!("{}", digest)),
+            Hash::SHA256(digest) => serializer.serialize_str(digest),
             Hash::SHA512(digest) => serializer.serialize_str(&format!("sha512:{}", digest)),
             Hash::SHA1(digest) => serializer.serialize_str(&format!("sha1:{}", digest)),
             Hash::MD5(digest) => serializer.serialize_str(&format!("md5:{}", digest)),
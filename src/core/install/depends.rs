@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use super::Receipt;
+
+use crate::core::buckets::{Dependency, Manifest};
+use crate::core::config::*;
+use crate::error::ScoopieError;
+
+/// An app resolved from the buckets together with the bucket it was found in.
+pub struct Resolved {
+    pub app: String,
+    pub bucket: String,
+    pub manifest: Manifest,
+}
+
+#[derive(PartialEq)]
+enum Mark {
+    Visiting,
+    Done,
+}
+
+/// Looks up an app (either `app` or `bucket/app`) and returns its name, bucket and manifest.
+pub type Lookup<'a> = &'a dyn Fn(&str) -> Result<(String, String, Manifest), ScoopieError>;
+
+/// Resolves `query` and all of its (transitive) dependencies into installation order using
+/// `lookup`, dependencies come first and the requested app is always the last entry.
+pub fn resolve_with_depends(query: &str, lookup: Lookup) -> Result<Vec<Resolved>, ScoopieError> {
+    let mut marks = HashMap::new();
+    let mut order = vec![];
+    let mut path = vec![];
+
+    visit(
+        &Dependency::from(query),
+        lookup,
+        &mut marks,
+        &mut order,
+        &mut path,
+    )?;
+
+    Ok(order)
+}
+
+fn visit(
+    dependency: &Dependency,
+    lookup: Lookup,
+    marks: &mut HashMap<String, Mark>,
+    order: &mut Vec<Resolved>,
+    path: &mut Vec<String>,
+) -> Result<(), ScoopieError> {
+    let app = dependency.app.clone();
+    path.push(app.clone());
+
+    match marks.get(&app) {
+        Some(Mark::Done) => {
+            path.pop();
+            return Ok(());
+        }
+        Some(Mark::Visiting) => return Err(ScoopieError::DependencyCycle(path.join(" -> "))),
+        None => {}
+    }
+
+    marks.insert(app.clone(), Mark::Visiting);
+
+    let (app_name, bucket, manifest) = lookup(&dependency.to_string())?;
+
+    manifest
+        .depends()
+        .iter()
+        .try_for_each(|dependency| visit(dependency, lookup, marks, order, path))?;
+
+    marks.insert(app, Mark::Done);
+    path.pop();
+
+    order.push(Resolved {
+        app: app_name,
+        bucket,
+        manifest,
+    });
+
+    Ok(())
+}
+
+/// Features suggested by `manifest` that none of the currently installed apps provide.
+pub fn unmet_suggestions(manifest: &Manifest) -> Vec<(String, Vec<Dependency>)> {
+    manifest
+        .suggest()
+        .into_iter()
+        .filter(|(_, alternatives)| {
+            !alternatives
+                .iter()
+                .any(|alternative| Receipt::current(&alternative.app).is_ok())
+        })
+        .collect()
+}
+
+/// Installed apps whose active version depends on `app`.
+pub fn dependents(app: &str) -> Result<Vec<String>, ScoopieError> {
+    let apps_dir = Config::app_dir()?;
+
+    let dependents = Receipt::installed()?
+        .into_iter()
+        .filter(|receipt| receipt.app != app)
+        .filter(|receipt| {
+            let manifest_path = apps_dir
+                .join(&receipt.app)
+                .join("current")
+                .join("manifest.json");
+
+            Manifest::try_from(manifest_path).is_ok_and(|manifest| {
+                manifest
+                    .depends()
+                    .iter()
+                    .any(|dependency| dependency.app == app)
+            })
+        })
+        .map(|receipt| receipt.app)
+        .collect();
+
+    Ok(dependents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::test_manifest;
+
+    /// Resolves `app` against a bucket made of `apps` (name and `depends`).
+    fn resolve(app: &str, apps: &[(&str, &str)]) -> Result<Vec<String>, ScoopieError> {
+        let lookup = |query: &str| {
+            let name = Dependency::from(query).app;
+
+            apps.iter()
+                .find(|(app, _)| *app == name)
+                .map(|(app, depends)| {
+                    let fields = format!(r#""depends": [{depends}]"#);
+                    (
+                        app.to_string(),
+                        String::from("main"),
+                        test_manifest(&fields),
+                    )
+                })
+                .ok_or(ScoopieError::NoAppFound(name))
+        };
+
+        resolve_with_depends(app, &lookup)
+            .map(|order| order.into_iter().map(|resolved| resolved.app).collect())
+    }
+
+    #[test]
+    fn dependencies_come_first() {
+        let apps = [
+            ("app", r#""lib", "main/tool""#),
+            ("lib", r#""base""#),
+            ("tool", ""),
+            ("base", ""),
+        ];
+
+        assert_eq!(
+            resolve("app", &apps).unwrap(),
+            ["base", "lib", "tool", "app"]
+        );
+        assert_eq!(resolve("lib", &apps).unwrap(), ["base", "lib"]);
+        assert!(matches!(
+            resolve("missing", &apps),
+            Err(ScoopieError::NoAppFound(_))
+        ));
+    }
+
+    #[test]
+    fn shared_dependencies_resolve_once() {
+        let apps = [
+            ("app", r#""left", "right""#),
+            ("left", r#""base""#),
+            ("right", r#""base""#),
+            ("base", ""),
+        ];
+
+        assert_eq!(
+            resolve("app", &apps).unwrap(),
+            ["base", "left", "right", "app"]
+        );
+    }
+
+    #[test]
+    fn cycles_are_reported() {
+        let apps = [("app", r#""a""#), ("a", r#""b""#), ("b", r#""a""#)];
+
+        match resolve("app", &apps) {
+            Err(ScoopieError::DependencyCycle(cycle)) => assert_eq!(cycle, "app -> a -> b -> a"),
+            other => panic!("expected a cycle, got {other:?}"),
+        }
+
+        assert!(matches!(
+            resolve("self", &[("self", r#""self""#)]),
+            Err(ScoopieError::DependencyCycle(_))
+        ));
+    }
+}
@@ -0,0 +1,224 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{buckets::Manifest, config::*};
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+/// Exactly what an install changed in the user's environment, kept in the receipt so that
+/// uninstalling only ever reverts what Scoopie itself added.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct EnvChanges {
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+    /// Values `vars` replaced, the user's own settings are put back when reverting.
+    #[serde(default)]
+    pub replaced: BTreeMap<String, String>,
+}
+
+impl EnvChanges {
+    /// Adds `env_add_path` dirs (resolved against `current_dir`) to `PATH` and sets `env_set`.
+    pub fn apply(
+        app: &str,
+        current_dir: &Path,
+        manifest: &Manifest,
+        store: &dyn EnvStore<Error = ScoopieError>,
+    ) -> Result<Self, ScoopieError> {
+        let persist_dir = Config::persist_dir()?.join(app);
+
+        let dirs = manifest
+            .env_add_path()
+            .iter()
+            .map(|dir| match dir.trim_matches(['/', '\\']) {
+                "" | "." => current_dir.to_path_buf(),
+                dir => current_dir.join(dir),
+            })
+            .collect::<Vec<_>>();
+
+        let paths = store.add_to_path(&dirs)?;
+        let mut replaced = BTreeMap::new();
+
+        let vars = manifest
+            .env_set()
+            .into_iter()
+            .map(|(key, value)| {
+                // Same substitutions Scoop performs on `env_set` values.
+                let value = value
+                    .replace("$persist_dir", &persist_dir.to_string_lossy())
+                    .replace("$dir", &current_dir.to_string_lossy())
+                    .replace("$version", manifest.version.as_str())
+                    .replace("$app", app);
+
+                if let Some(previous) = store.get(&key)? {
+                    replaced.insert(key.clone(), previous);
+                }
+
+                store.set(&key, &value).map(|_| (key, value))
+            })
+            .collect::<Result<BTreeMap<_, _>, _>>()?;
+
+        Ok(Self {
+            paths,
+            vars,
+            replaced,
+        })
+    }
+
+    /// Applies `self` again, used to put back changes reverted by a failed install.
+    pub fn restore(&self, store: &dyn EnvStore<Error = ScoopieError>) -> Result<(), ScoopieError> {
+        store.add_to_path(&self.paths)?;
+
+        self.vars
+            .iter()
+            .try_for_each(|(key, value)| store.set(key, value))
+    }
+
+    /// Undoes `self`, putting back the values it replaced. Variables changed by someone else since
+    /// they were set are left alone.
+    pub fn revert(&self, store: &dyn EnvStore<Error = ScoopieError>) -> Result<(), ScoopieError> {
+        store.remove_from_path(&self.paths)?;
+
+        self.vars.iter().try_for_each(|(key, value)| {
+            match store.get(key)?.is_some_and(|current| current == *value) {
+                true => match self.replaced.get(key) {
+                    Some(previous) => store.set(key, previous),
+                    None => store.remove(key),
+                },
+                false => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn apply_and_revert_round_trip() {
+        let dir = test_dir("env-round-trip");
+        let store = FileEnv::new(dir.join("env.json"));
+        let current = dir.join("apps").join("foo").join("current");
+
+        store.set_paths(&[PathBuf::from("/usr/bin")]).unwrap();
+        store.set("KEEP", "1").unwrap();
+
+        let manifest = test_manifest(
+            r#""env_add_path": ["bin", "."], "env_set": {
+                "FOO_HOME": "$dir", "FOO_DATA": "$persist_dir\\data", "FOO_ID": "$app-$version"
+            }"#,
+        );
+        let changes = EnvChanges::apply("foo", &current, &manifest, &store).unwrap();
+
+        let added = vec![current.join("bin"), current.clone()];
+        assert_eq!(changes.paths, added);
+        assert_eq!(
+            store.paths().unwrap(),
+            [added.clone(), vec![PathBuf::from("/usr/bin")]].concat()
+        );
+
+        let persist_dir = Config::persist_dir().unwrap().join("foo");
+        let expected = [
+            (
+                "FOO_DATA",
+                format!("{}\\data", persist_dir.to_string_lossy()),
+            ),
+            ("FOO_HOME", current.to_string_lossy().to_string()),
+            ("FOO_ID", String::from("foo-1.2")),
+        ];
+
+        expected.iter().for_each(|(key, value)| {
+            assert_eq!(changes.vars.get(*key), Some(value));
+            assert_eq!(store.get(key).unwrap().as_ref(), Some(value));
+        });
+
+        changes.revert(&store).unwrap();
+
+        assert_eq!(store.paths().unwrap(), vec![PathBuf::from("/usr/bin")]);
+        expected
+            .iter()
+            .for_each(|(key, _)| assert_eq!(store.get(key).unwrap(), None));
+        assert_eq!(store.get("KEEP").unwrap().as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn paths_already_present_are_not_recorded() {
+        let dir = test_dir("env-existing-path");
+        let store = FileEnv::new(dir.join("env.json"));
+        let current = dir.join("current");
+
+        store.set_paths(&[current.join("bin")]).unwrap();
+
+        let manifest = test_manifest(r#""env_add_path": ["bin", "tools"]"#);
+        let changes = EnvChanges::apply("foo", &current, &manifest, &store).unwrap();
+        assert_eq!(changes.paths, vec![current.join("tools")]);
+
+        changes.revert(&store).unwrap();
+        assert_eq!(store.paths().unwrap(), vec![current.join("bin")]);
+    }
+
+    #[test]
+    fn revert_leaves_variables_changed_since() {
+        let dir = test_dir("env-changed-since");
+        let store = FileEnv::new(dir.join("env.json"));
+
+        let manifest = test_manifest(r#""env_set": {"FOO": "ours", "BAR": "ours"}"#);
+        let changes = EnvChanges::apply("foo", &dir, &manifest, &store).unwrap();
+
+        store.set("BAR", "theirs").unwrap();
+        changes.revert(&store).unwrap();
+
+        assert_eq!(store.get("FOO").unwrap(), None);
+        assert_eq!(store.get("BAR").unwrap().as_deref(), Some("theirs"));
+    }
+
+    #[test]
+    fn revert_puts_back_replaced_variables() {
+        let dir = test_dir("env-replaced");
+        let store = FileEnv::new(dir.join("env.json"));
+
+        store.set("JAVA_HOME", "C:\\jdk-mine").unwrap();
+
+        let manifest = test_manifest(r#""env_set": {"JAVA_HOME": "$dir", "FOO": "ours"}"#);
+        let changes = EnvChanges::apply("foo", &dir, &manifest, &store).unwrap();
+
+        assert_eq!(
+            store.get("JAVA_HOME").unwrap(),
+            Some(dir.to_string_lossy().to_string())
+        );
+        assert_eq!(
+            changes.replaced,
+            BTreeMap::from([("JAVA_HOME".into(), "C:\\jdk-mine".into())])
+        );
+
+        changes.revert(&store).unwrap();
+
+        assert_eq!(
+            store.get("JAVA_HOME").unwrap().as_deref(),
+            Some("C:\\jdk-mine")
+        );
+        assert_eq!(store.get("FOO").unwrap(), None);
+    }
+
+    #[test]
+    fn restore_puts_back_reverted_changes() {
+        let dir = test_dir("env-restore");
+        let store = FileEnv::new(dir.join("env.json"));
+
+        let manifest = test_manifest(r#""env_add_path": "bin", "env_set": {"FOO": "$dir"}"#);
+        let changes = EnvChanges::apply("foo", &dir, &manifest, &store).unwrap();
+        let applied = (store.paths().unwrap(), store.get("FOO").unwrap());
+
+        changes.revert(&store).unwrap();
+        assert!(store.paths().unwrap().is_empty());
+
+        changes.restore(&store).unwrap();
+        assert_eq!((store.paths().unwrap(), store.get("FOO").unwrap()), applied);
+    }
+}
@@ -1,8 +1,26 @@
-use std::path::PathBuf;
+use std::{
+    fs::{self, File},
+    io::{self, BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+};
 
-#[derive(Debug)]
+use bzip2::read::BzDecoder;
+use flate2::read::GzDecoder;
+use xz2::read::XzDecoder;
+
+use crate::core::config::*;
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FileKind {
+    ZipArchive,
     SevenZipArchive,
+    TarArchive,
+    GZipArchive,
+    XzArchive,
+    BZip2Archive,
+    ZstdArchive,
     ExeFile,
     MsiFile,
     InnoFile,
@@ -10,25 +28,226 @@ pub enum FileKind {
 }
 
 impl FileKind {
-    pub fn infer(path: &PathBuf) -> Self {
-        // println!("{:?}", path);
-
-        // let kind = infer::get_from_path(path).unwrap();
-        // println!("{:?}", kind);
-
-        // match kind {
-        //     Some(k) => match k.mime_type() {
-        //         "application/zip" => Self::ZipArchive,
-        //         "application/x-7z-compressed" => Self::SevenZipArchive,
-        //         "application/x-tar" => Self::TarArchive,
-        //         "application/gzip" => Self::GZipArchive,
-        //         "application/x-xz" => Self::LZMAArchive,
-        //         "application/x-lzip" => Self::LZMAArchive,
-        //         _ => Self::Other,
-        //     },
-        //     None => Self::Other,
-        // }
-
-        Self::Other
+    pub fn is_archive(&self) -> bool {
+        !matches!(self, Self::ExeFile | Self::Other)
+    }
+
+    /// Sniffs the kind of file by looking at its magic bytes, extensions are never trusted as
+    /// manifests often rename downloads (e.g. `setup.exe#/dl.7z`).
+    pub fn infer(path: &Path) -> Result<Self, ScoopieError> {
+        let file = File::open(path).map_err(|_| ScoopieError::FailedToOpenFile(path.into()))?;
+
+        let mut header = Vec::with_capacity(512);
+        file.take(512)
+            .read_to_end(&mut header)
+            .map_err(|_| ScoopieError::FailedToReadFile(path.into()))?;
+
+        Ok(Self::from_magic(&header))
+    }
+
+    fn from_magic(bytes: &[u8]) -> Self {
+        match bytes {
+            [0x50, 0x4B, 0x03, 0x04, ..]
+            | [0x50, 0x4B, 0x05, 0x06, ..]
+            | [0x50, 0x4B, 0x07, 0x08, ..] => Self::ZipArchive,
+            [0x37, 0x7A, 0xBC, 0xAF, 0x27, 0x1C, ..] => Self::SevenZipArchive,
+            [0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00, ..] => Self::XzArchive,
+            [0x1F, 0x8B, ..] => Self::GZipArchive,
+            [0x42, 0x5A, 0x68, ..] => Self::BZip2Archive,
+            [0x28, 0xB5, 0x2F, 0xFD, ..] => Self::ZstdArchive,
+            [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1, ..] => Self::MsiFile,
+            [0x4D, 0x5A, ..] => Self::ExeFile,
+            _ if bytes.get(257..262) == Some(b"ustar") => Self::TarArchive,
+            _ => Self::Other,
+        }
+    }
+}
+
+pub struct Extractor;
+
+impl Extractor {
+    /// Extracts `src` into `dest`, files which aren't archives are copied as `name`.
+    pub fn extract(
+        src: &Path,
+        name: &str,
+        dest: &Path,
+        kind: FileKind,
+    ) -> Result<(), ScoopieError> {
+        PathBuf::create(&dest.to_path_buf())?;
+
+        let failed = || ScoopieError::FailedToExtract(src.to_path_buf());
+        let open = || File::open(src).map_err(|_| ScoopieError::FailedToOpenFile(src.into()));
+
+        match kind {
+            FileKind::ZipArchive => zip::ZipArchive::new(BufReader::new(open()?))
+                .and_then(|mut archive| archive.extract(dest))
+                .map_err(|_| failed()),
+            // Unlike zip and tar, entry names aren't checked for escaping `dest` by the crate.
+            FileKind::SevenZipArchive => {
+                sevenz_rust::decompress_file_with_extract_fn(src, dest, |entry, reader, _| {
+                    let path = contained(entry.name()).ok_or_else(|| {
+                        sevenz_rust::Error::other(format!("unsafe entry: {}", entry.name()))
+                    })?;
+
+                    sevenz_rust::default_entry_extract_fn(entry, reader, &dest.join(path))
+                })
+                .map_err(|_| failed())
+            }
+            FileKind::TarArchive => tar::Archive::new(open()?)
+                .unpack(dest)
+                .map_err(|_| failed()),
+            FileKind::GZipArchive => Self::decompress(src, GzDecoder::new(open()?), name, dest),
+            FileKind::XzArchive => Self::decompress(src, XzDecoder::new(open()?), name, dest),
+            FileKind::BZip2Archive => Self::decompress(src, BzDecoder::new(open()?), name, dest),
+            FileKind::ZstdArchive => {
+                let decoder = zstd::Decoder::new(open()?).map_err(|_| failed())?;
+                Self::decompress(src, decoder, name, dest)
+            }
+            FileKind::MsiFile => Self::run(
+                src,
+                &format!(
+                    "msiexec.exe /a {} /qn TARGETDIR={}",
+                    Pwsh::quote(&src.to_string_lossy()),
+                    Pwsh::quote(&dest.to_string_lossy())
+                ),
+            ),
+            FileKind::InnoFile => {
+                let innounp = Config::app_dir()?.join("scoopie\\bin\\innounp.exe");
+
+                match innounp.exists() {
+                    true => Self::run(
+                        src,
+                        &format!(
+                            "& {} -x {} {} -y '-c{{app}}'",
+                            Pwsh::quote(&innounp.to_string_lossy()),
+                            Pwsh::quote(&format!("-d{}", dest.display())),
+                            Pwsh::quote(&src.to_string_lossy())
+                        ),
+                    ),
+                    false => Err(ScoopieError::FileNotExist(innounp)),
+                }
+            }
+            FileKind::ExeFile | FileKind::Other => fs::copy(src, dest.join(name))
+                .map(|_| ())
+                .map_err(|_| failed()),
+        }
+    }
+
+    /// Compressed streams either wrap a tarball (`.tar.gz`, `.tar.xz`, ...) or a single file,
+    /// in the latter case the file is written out without its compression extension.
+    fn decompress<R: Read>(
+        src: &Path,
+        reader: R,
+        name: &str,
+        dest: &Path,
+    ) -> Result<(), ScoopieError> {
+        let failed = || ScoopieError::FailedToExtract(src.to_path_buf());
+
+        let mut reader = BufReader::new(reader);
+        let mut header = Vec::with_capacity(512);
+        (&mut reader)
+            .take(512)
+            .read_to_end(&mut header)
+            .map_err(|_| failed())?;
+
+        let is_tarball = FileKind::from_magic(&header) == FileKind::TarArchive;
+        let mut stream = Cursor::new(header).chain(reader);
+
+        match is_tarball {
+            true => tar::Archive::new(stream).unpack(dest).map_err(|_| failed()),
+            false => {
+                let file_name = Path::new(name).file_stem().unwrap_or_default();
+                let mut file = File::create(dest.join(file_name)).map_err(|_| failed())?;
+                io::copy(&mut stream, &mut file)
+                    .map(|_| ())
+                    .map_err(|_| failed())
+            }
+        }
+    }
+
+    fn run(src: &Path, prog: &str) -> Result<(), ScoopieError> {
+        Pwsh::run(None, prog)
+            .map(|_| ())
+            .map_err(|_| ScoopieError::FailedToExtract(src.to_path_buf()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sevenz_rust::{SevenZArchiveEntry, SevenZWriter};
+
+    use super::*;
+    use crate::utils::test_dir;
+
+    /// A 7z archive at `path` holding a small file under each of `names`.
+    fn seven_zip(dir: &Path, path: &Path, names: &[&str]) {
+        let content = dir.join("content.txt");
+        fs::write(&content, "scoopie").unwrap();
+
+        let mut writer = SevenZWriter::create(path).unwrap();
+
+        names.iter().for_each(|name| {
+            let entry = SevenZArchiveEntry::from_path(&content, name.to_string());
+            writer
+                .push_archive_entry(entry, Some(File::open(&content).unwrap()))
+                .unwrap();
+        });
+
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn kinds_from_magic_bytes() {
+        let mut tarball = vec![0; 512];
+        tarball[257..262].copy_from_slice(b"ustar");
+
+        let cases: [(&[u8], FileKind); 13] = [
+            (b"PK\x03\x04rest", FileKind::ZipArchive),
+            (b"PK\x05\x06", FileKind::ZipArchive),
+            (b"PK\x07\x08", FileKind::ZipArchive),
+            (b"7z\xBC\xAF\x27\x1C", FileKind::SevenZipArchive),
+            (b"\x1F\x8B\x08", FileKind::GZipArchive),
+            (b"\xFD7zXZ\x00", FileKind::XzArchive),
+            (b"BZh9", FileKind::BZip2Archive),
+            (b"\x28\xB5\x2F\xFD", FileKind::ZstdArchive),
+            (b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1", FileKind::MsiFile),
+            (b"MZ\x90\x00", FileKind::ExeFile),
+            (&tarball, FileKind::TarArchive),
+            (b"PK", FileKind::Other),
+            (b"", FileKind::Other),
+        ];
+
+        cases.iter().for_each(|(bytes, kind)| {
+            assert_eq!(FileKind::from_magic(bytes), *kind, "{bytes:02X?}");
+        });
+    }
+
+    #[test]
+    fn seven_zip_entries_stay_inside() {
+        let dir = test_dir("extract-7z");
+        let dest = dir.join("dest");
+
+        let archive = dir.join("good.7z");
+        seven_zip(&dir, &archive, &["bin/tool.txt"]);
+        assert_eq!(
+            FileKind::infer(&archive).unwrap(),
+            FileKind::SevenZipArchive
+        );
+
+        Extractor::extract(&archive, "good.7z", &dest, FileKind::SevenZipArchive).unwrap();
+        assert!(dest.join("bin").join("tool.txt").is_file());
+
+        ["../evil.txt", "bin/../../evil.txt"]
+            .iter()
+            .for_each(|name| {
+                let archive = dir.join("evil.7z");
+                seven_zip(&dir, &archive, &[name]);
+
+                assert!(
+                    Extractor::extract(&archive, "evil.7z", &dest, FileKind::SevenZipArchive)
+                        .is_err()
+                );
+                assert!(!dir.join("evil.txt").exists(), "{name:?} was extracted");
+            });
     }
 }
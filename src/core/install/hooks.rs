@@ -0,0 +1,310 @@
+use std::{
+    fmt,
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{buckets::Manifest, config::*};
+use crate::error::ScoopieError;
+use crate::utils::Pwsh;
+
+/// Manifest scripts run around (un)installing an app.
+#[derive(Debug, Clone, Copy)]
+pub enum Hook {
+    PreInstall,
+    PostInstall,
+    PreUninstall,
+    PostUninstall,
+}
+
+impl fmt::Display for Hook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Hook::PreInstall => write!(f, "pre_install"),
+            Hook::PostInstall => write!(f, "post_install"),
+            Hook::PreUninstall => write!(f, "pre_uninstall"),
+            Hook::PostUninstall => write!(f, "post_uninstall"),
+        }
+    }
+}
+
+impl Hook {
+    fn lines(&self, manifest: &Manifest) -> Vec<String> {
+        match self {
+            Hook::PreInstall => manifest.pre_install(),
+            Hook::PostInstall => manifest.post_install(),
+            Hook::PreUninstall => manifest.pre_uninstall(),
+            Hook::PostUninstall => manifest.post_uninstall(),
+        }
+    }
+}
+
+/// Interpreter for manifest scripts, returns whether the script succeeded and what it printed.
+pub trait ScriptRunner {
+    fn run(&self, script: &str) -> Result<(bool, String), ScoopieError>;
+
+    /// Whether scripts actually run, steps depending on what they did are skipped otherwise.
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// Runs scripts through Windows PowerShell, like Scoop does.
+pub struct PwshRunner;
+
+impl ScriptRunner for PwshRunner {
+    fn run(&self, script: &str) -> Result<(bool, String), ScoopieError> {
+        Pwsh::exec(script)
+    }
+}
+
+/// Used with `--no-scripts`, never runs anything.
+pub struct NoScripts;
+
+impl ScriptRunner for NoScripts {
+    fn run(&self, _: &str) -> Result<(bool, String), ScoopieError> {
+        Ok((true, String::from("Skipped (--no-scripts)")))
+    }
+
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+pub fn script_runner(no_scripts: bool) -> Box<dyn ScriptRunner> {
+    match no_scripts {
+        true => Box::new(NoScripts),
+        false => Box::new(PwshRunner),
+    }
+}
+
+pub struct Hooks;
+
+impl Hooks {
+    /// Runs `hook` of `manifest` with Scoop's standard variables in scope, `dir` being the app's
+    /// directory. Output is appended to `log` when given and printed otherwise.
+    pub fn run(
+        hook: Hook,
+        app: &str,
+        dir: &Path,
+        manifest: &Manifest,
+        runner: &dyn ScriptRunner,
+        log: Option<&Path>,
+    ) -> Result<(), ScoopieError> {
+        Self::script(
+            &hook.to_string(),
+            &hook.lines(manifest),
+            app,
+            dir,
+            manifest,
+            runner,
+            log,
+        )
+    }
+
+    /// Runs the script `lines` known as `name` the same way manifest hooks are run.
+    pub fn script(
+        name: &str,
+        lines: &[String],
+        app: &str,
+        dir: &Path,
+        manifest: &Manifest,
+        runner: &dyn ScriptRunner,
+        log: Option<&Path>,
+    ) -> Result<(), ScoopieError> {
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let variables = [
+            ("app", app.to_string()),
+            ("dir", dir.to_string_lossy().to_string()),
+            ("version", manifest.version.to_string()),
+            (
+                "persist_dir",
+                Config::persist_dir()?
+                    .join(app)
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            ("architecture", Config::arch()?.to_string()),
+            (
+                "bucketsdir",
+                Config::buckets_dir()?.to_string_lossy().to_string(),
+            ),
+        ];
+
+        let prelude = variables
+            .iter()
+            .map(|(name, value)| format!("${name} = {}", Pwsh::quote(value)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let script = format!(
+            "$ErrorActionPreference = 'Stop'\n{prelude}\n{}",
+            lines.join("\n")
+        );
+
+        let (success, output) = runner.run(&script)?;
+
+        match log {
+            Some(log) => Self::log(log, name, &output)?,
+            None if !output.is_empty() => println!("{output}"),
+            None => {}
+        }
+
+        match success {
+            true => Ok(()),
+            false => Err(ScoopieError::HookFailed(app.into(), name.into())),
+        }
+    }
+
+    fn log(log: &Path, name: &str, output: &str) -> Result<(), ScoopieError> {
+        let failed = || ScoopieError::FailedToWriteFile(PathBuf::from(log));
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log)
+            .map_err(|_| failed())?;
+
+        writeln!(file, "==> {name}\n{output}").map_err(|_| failed())
+    }
+}
+
+/// Remembers every script it's given instead of running it.
+#[cfg(test)]
+pub struct Recorder {
+    pub scripts: std::cell::RefCell<Vec<String>>,
+    success: bool,
+}
+
+#[cfg(test)]
+impl Recorder {
+    pub fn new(success: bool) -> Self {
+        Self {
+            scripts: Default::default(),
+            success,
+        }
+    }
+}
+
+#[cfg(test)]
+impl ScriptRunner for Recorder {
+    fn run(&self, script: &str) -> Result<(bool, String), ScoopieError> {
+        self.scripts.borrow_mut().push(script.into());
+        let name = script.lines().last().unwrap_or_default();
+        Ok((self.success, format!("ran {name}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::utils::{test_dir, test_manifest};
+
+    fn manifest() -> Manifest {
+        test_manifest(
+            r#""pre_install": ["echo pre", "echo pre_install"],
+            "post_install": "echo post_install",
+            "pre_uninstall": "echo pre_uninstall""#,
+        )
+    }
+
+    #[test]
+    fn scripts_start_with_the_prelude() {
+        let dir = test_dir("hooks-prelude");
+        let runner = Recorder::new(true);
+
+        Hooks::run(Hook::PreInstall, "foo", &dir, &manifest(), &runner, None).unwrap();
+
+        let variables = [
+            ("app", String::from("foo")),
+            ("dir", dir.to_string_lossy().to_string()),
+            ("version", String::from("1.2")),
+            (
+                "persist_dir",
+                Config::persist_dir()
+                    .unwrap()
+                    .join("foo")
+                    .to_string_lossy()
+                    .to_string(),
+            ),
+            ("architecture", Config::arch().unwrap().to_string()),
+            (
+                "bucketsdir",
+                Config::buckets_dir().unwrap().to_string_lossy().to_string(),
+            ),
+        ];
+
+        let expected = ["$ErrorActionPreference = 'Stop'".to_string()]
+            .into_iter()
+            .chain(
+                variables
+                    .iter()
+                    .map(|(name, value)| format!("${name} = {}", Pwsh::quote(value))),
+            )
+            .chain(["echo pre".to_string(), "echo pre_install".to_string()])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(*runner.scripts.borrow(), vec![expected]);
+    }
+
+    #[test]
+    fn hooks_run_and_log_in_order() {
+        let dir = test_dir("hooks-order");
+        let log = dir.join("install.log");
+        let runner = Recorder::new(true);
+        let manifest = manifest();
+
+        [
+            Hook::PreInstall,
+            Hook::PostInstall,
+            Hook::PreUninstall,
+            Hook::PostUninstall,
+        ]
+        .iter()
+        .for_each(|hook| {
+            Hooks::run(*hook, "foo", &dir, &manifest, &runner, Some(&log)).unwrap();
+        });
+
+        // `post_uninstall` has no lines, so nothing is run for it.
+        let last_lines = runner
+            .scripts
+            .borrow()
+            .iter()
+            .map(|script| script.lines().last().unwrap_or_default().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            last_lines,
+            [
+                "echo pre_install",
+                "echo post_install",
+                "echo pre_uninstall"
+            ]
+        );
+
+        assert_eq!(
+            fs::read_to_string(&log).unwrap(),
+            "==> pre_install\nran echo pre_install\n\
+             ==> post_install\nran echo post_install\n\
+             ==> pre_uninstall\nran echo pre_uninstall\n"
+        );
+    }
+
+    #[test]
+    fn failing_scripts_fail_the_hook() {
+        let dir = test_dir("hooks-failure");
+        let runner = Recorder::new(false);
+
+        let result = Hooks::run(Hook::PostInstall, "foo", &dir, &manifest(), &runner, None);
+        assert!(matches!(
+            result,
+            Err(ScoopieError::HookFailed(app, hook)) if app == "foo" && hook == "post_install"
+        ));
+    }
+}
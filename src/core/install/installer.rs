@@ -0,0 +1,263 @@
+use std::path::{Path, PathBuf};
+
+use super::hooks::{Hooks, ScriptRunner};
+use super::inside;
+
+use crate::core::buckets::{Installer, Manifest};
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+/// Runs the `installer`/`uninstaller` of manifests.
+pub struct Installers;
+
+/// The app an (un)installer runs for.
+struct Target<'a> {
+    app: &'a str,
+    version_dir: &'a Path,
+    manifest: &'a Manifest,
+    runner: &'a dyn ScriptRunner,
+    log: Option<&'a Path>,
+}
+
+impl Installers {
+    /// Runs the manifest's installer from `version_dir`, `default_file` is used when it has no
+    /// `file` (Scoop runs the download itself in that case). Like Scoop, a `script` takes the
+    /// place of running the file. The file is removed afterwards unless `keep`.
+    pub fn install(
+        app: &str,
+        version_dir: &Path,
+        default_file: &str,
+        manifest: &Manifest,
+        runner: &dyn ScriptRunner,
+        log: &Path,
+    ) -> Result<(), ScoopieError> {
+        let Some(installer) = manifest.installer() else {
+            return Ok(());
+        };
+
+        let target = Target {
+            app,
+            version_dir,
+            manifest,
+            runner,
+            log: Some(log),
+        };
+
+        let file = match installer.script.is_some() {
+            true => None,
+            false => Some(installer.file.as_deref().unwrap_or(default_file)),
+        };
+
+        let file = target.run("installer", &installer, file)?;
+
+        // Nothing ran with `--no-scripts`, so whatever it would have done with the file is left as is.
+        if !runner.enabled() {
+            return Ok(());
+        }
+
+        match (file, installer.keep) {
+            (Some(file), false) if file.exists() => file.rm(),
+            _ => Ok(()),
+        }
+    }
+
+    /// Runs the manifest's uninstaller, if any, from `version_dir`.
+    pub fn uninstall(
+        app: &str,
+        version_dir: &Path,
+        manifest: &Manifest,
+        runner: &dyn ScriptRunner,
+    ) -> Result<(), ScoopieError> {
+        let Some(uninstaller) = manifest.uninstaller() else {
+            return Ok(());
+        };
+
+        let target = Target {
+            app,
+            version_dir,
+            manifest,
+            runner,
+            log: None,
+        };
+
+        let file = match uninstaller.script.is_some() {
+            true => None,
+            false => uninstaller.file.as_deref(),
+        };
+
+        target.run("uninstaller", &uninstaller, file).map(|_| ())
+    }
+}
+
+impl Target<'_> {
+    /// Runs `file` (relative to the version directory) or else the installer's script, returning
+    /// the path of the file that ran.
+    fn run(
+        &self,
+        name: &str,
+        installer: &Installer,
+        file: Option<&str>,
+    ) -> Result<Option<PathBuf>, ScoopieError> {
+        let mut lines = vec![];
+
+        let file = file
+            .map(|file| inside(self.app, file).map(|file| self.version_dir.join(file)))
+            .transpose()?;
+
+        if let Some(file) = &file {
+            if !file.exists() {
+                return Err(ScoopieError::FileNotExist(file.clone()));
+            }
+
+            let args = installer
+                .args(self.version_dir, &self.manifest.version)
+                .iter()
+                .map(|arg| Pwsh::quote(&quote_arg(arg)))
+                .collect::<Vec<_>>();
+
+            let args = match args.is_empty() {
+                true => String::new(),
+                false => format!(" -ArgumentList @({})", args.join(", ")),
+            };
+
+            lines.push(format!(
+                "$process = Start-Process -FilePath {}{args} -Wait -PassThru",
+                Pwsh::quote(&file.to_string_lossy())
+            ));
+            lines.push(String::from(
+                "if ($process.ExitCode -ne 0) { exit $process.ExitCode }",
+            ));
+        } else {
+            lines.extend(installer.script());
+        }
+
+        Hooks::script(
+            name,
+            &lines,
+            self.app,
+            self.version_dir,
+            self.manifest,
+            self.runner,
+            self.log,
+        )?;
+
+        Ok(file)
+    }
+}
+
+/// `Start-Process` joins `-ArgumentList` with plain spaces, so arguments that would be split
+/// apart are quoted the way Windows programs parse their command line.
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.into();
+    }
+
+    let mut quoted = String::from('"');
+    let mut backslashes = 0;
+
+    arg.chars().for_each(|c| match c {
+        '\\' => {
+            backslashes += 1;
+            quoted.push(c);
+        }
+        '"' => {
+            // Backslashes only escape when followed by a quote, so those get escaped as well.
+            quoted.push_str(&"\\".repeat(backslashes + 1));
+            quoted.push(c);
+            backslashes = 0;
+        }
+        _ => {
+            backslashes = 0;
+            quoted.push(c);
+        }
+    });
+
+    quoted.push_str(&"\\".repeat(backslashes));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::core::install::hooks::Recorder;
+    use crate::utils::{test_dir, test_manifest};
+
+    fn install(dir: &Path, fields: &str, runner: &Recorder) -> Result<(), ScoopieError> {
+        let manifest = test_manifest(fields);
+        Installers::install(
+            "foo",
+            dir,
+            "setup.exe",
+            &manifest,
+            runner,
+            &dir.join("install.log"),
+        )
+    }
+
+    #[test]
+    fn scripts_take_the_place_of_the_file() {
+        let dir = test_dir("installer-script");
+        fs::write(dir.join("setup.exe"), "").unwrap();
+        let runner = Recorder::new(true);
+
+        install(
+            &dir,
+            r#""installer": { "file": "setup.exe", "script": "echo installing" }"#,
+            &runner,
+        )
+        .unwrap();
+
+        let scripts = runner.scripts.borrow();
+        assert_eq!(scripts.len(), 1);
+        assert!(scripts[0].ends_with("echo installing"));
+        assert!(!scripts[0].contains("Start-Process"));
+        assert!(dir.join("setup.exe").exists());
+    }
+
+    #[test]
+    fn files_run_and_get_removed() {
+        let dir = test_dir("installer-file");
+        fs::write(dir.join("setup.exe"), "").unwrap();
+        let runner = Recorder::new(true);
+
+        install(&dir, r#""installer": { "args": "/S" }"#, &runner).unwrap();
+
+        assert!(runner.scripts.borrow()[0].contains("setup.exe' -ArgumentList @('/S')"));
+        assert!(!dir.join("setup.exe").exists());
+    }
+
+    #[test]
+    fn files_stay_inside_the_app() {
+        let dir = test_dir("installer-outside");
+        let runner = Recorder::new(true);
+
+        ["../setup.exe", "/setup.exe", "C:\\\\setup.exe"]
+            .iter()
+            .for_each(|file| {
+                let fields = format!(r#""installer": {{ "file": "{file}" }}"#);
+
+                assert!(matches!(
+                    install(&dir, &fields, &runner),
+                    Err(ScoopieError::PathOutsideApp(_, _))
+                ));
+            });
+
+        assert!(runner.scripts.borrow().is_empty());
+    }
+
+    #[test]
+    fn arguments_survive_being_joined() {
+        assert_eq!(quote_arg("/S"), "/S");
+        assert_eq!(quote_arg("C:\\apps\\foo"), "C:\\apps\\foo");
+        assert_eq!(quote_arg(""), "\"\"");
+        assert_eq!(
+            quote_arg("/DIR=C:\\Program Files\\foo\\"),
+            "\"/DIR=C:\\Program Files\\foo\\\\\""
+        );
+        assert_eq!(quote_arg("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(quote_arg("a\\\"b"), "\"a\\\\\\\"b\"");
+    }
+}
@@ -1,68 +1,593 @@
+mod depends;
+mod env;
 mod extractor;
+mod hooks;
+mod installer;
+mod persist;
+mod receipt;
+mod shim;
+mod transaction;
+mod update;
 
-use extractor::FileKind;
+use std::fs;
+use std::iter::zip;
+use std::path::{Path, PathBuf};
+
+use depends::{dependents, resolve_with_depends, unmet_suggestions, Resolved};
+pub use env::EnvChanges;
+use extractor::{Extractor, FileKind};
+pub use hooks::{script_runner, ScriptRunner};
+use hooks::{Hook, Hooks};
+use installer::Installers;
+use persist::Persists;
+pub use receipt::Receipt;
+pub use shim::Shims;
+use transaction::Transaction;
+pub use update::*;
+use url::Url;
 
 use crate::core::{
-    buckets::{Buckets, Query},
+    buckets::{Buckets, Manifest, MetaData, Query},
     config::*,
     download::*,
 };
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+/// Installs `app` (either `app` or `bucket/app`), installing its missing dependencies first.
+/// Unmet suggestions are either reported or, when `with_suggested`, installed as well.
+pub fn install(
+    app: &str,
+    with_suggested: bool,
+    scripts: &dyn ScriptRunner,
+) -> Result<(), ScoopieError> {
+    let mut order = resolve_with_depends(app, &resolve_app)?;
+    let requested = order.pop().ok_or(ScoopieError::NoAppFound(app.into()))?;
+
+    // Dependencies are only there for the app, so they go away too if it fails to install.
+    Transaction::run(|tx| {
+        install_depends(tx, &requested.app, &order, scripts)?;
+
+        install_into(
+            tx,
+            &requested.app,
+            &requested.bucket,
+            &requested.manifest,
+            scripts,
+        )
+    })?;
+
+    let unmet = unmet_suggestions(&requested.manifest);
+
+    if unmet.is_empty() {
+        return Ok(());
+    }
+
+    match with_suggested {
+        true => unmet.iter().try_for_each(|(feature, alternatives)| {
+            // An app installed for an earlier feature may already cover this one.
+            if alternatives
+                .iter()
+                .any(|alternative| Receipt::current(&alternative.app).is_ok())
+            {
+                return Ok(());
+            }
 
-pub fn install(app: &str) {
+            // First alternative that can actually be found in the synced buckets wins. Probing
+            // stays quiet, installing it warns when several buckets ship it.
+            let names = alternatives
+                .iter()
+                .map(|alternative| alternative.app.clone())
+                .collect::<Vec<_>>();
+            let buckets = Buckets::query_apps(&names)?;
+
+            match alternatives
+                .iter()
+                .find(|alternative| match &alternative.bucket {
+                    Some(bucket) => buckets.get_app_from(&alternative.app, bucket).is_some(),
+                    None => buckets.find_app(&alternative.app).is_some(),
+                }) {
+                Some(alternative) => {
+                    println!("Installing {alternative} to provide {feature}");
+                    install(&alternative.to_string(), false, scripts)
+                }
+                None => {
+                    eprintln!("No available app provides suggested {feature}");
+                    Ok(())
+                }
+            }
+        }),
+        false => {
+            println!("{} suggests installing:", requested.app);
+            unmet.iter().for_each(|(feature, alternatives)| {
+                let alternatives = alternatives
+                    .iter()
+                    .map(|alternative| alternative.to_string())
+                    .collect::<Vec<_>>()
+                    .join(" | ");
+
+                println!("  {feature}: {alternatives}");
+            });
+            println!("Hint: Use \"--with-suggested\" to install them");
+
+            Ok(())
+        }
+    }
+}
+
+/// Looks up `app` (either `app` or `bucket/app`) and returns its name, bucket and manifest.
+fn resolve_app(app: &str) -> Result<(String, String, Manifest), ScoopieError> {
     let query = app.trim().to_lowercase();
 
-    let (app_name, manifest) = match query.split_once('/') {
-        Some((bucket, app_name)) => (
-            app_name,
-            Buckets::query_app(app_name)
-                .unwrap()
-                .get_app_from(app_name, bucket),
-        ),
-        None => (app, Buckets::query_app(app).unwrap().get_app(app)),
-    };
+    match query.split_once('/') {
+        Some((bucket, app_name)) => {
+            let manifest = Buckets::query_app(app_name)?
+                .get_app_from(app_name, bucket)
+                .ok_or(ScoopieError::NoAppFoundInBucket(
+                    app_name.into(),
+                    bucket.into(),
+                ))?;
 
-    let manifest = manifest.unwrap();
-    let file_name = Downloader::download(app_name, true).unwrap();
-    let cache_dir = Config::cache_dir().unwrap();
+            Ok((app_name.into(), bucket.into(), manifest))
+        }
+        None => {
+            let (bucket, manifest) = Buckets::query_app(&query)?
+                .resolve_app(&query)
+                .ok_or(ScoopieError::NoAppFound(query.to_string()))?;
 
-    let srcs = file_name
+            Ok((query, bucket, manifest))
+        }
+    }
+}
+
+/// Installs the dependencies in `order` that aren't installed yet, recording how to undo them on `tx`.
+fn install_depends(
+    tx: &mut Transaction,
+    app_name: &str,
+    order: &[Resolved],
+    scripts: &dyn ScriptRunner,
+) -> Result<(), ScoopieError> {
+    order
         .iter()
-        .map(|f| match f {
-            DownloadStatus::Downloaded(s) => s,
-            DownloadStatus::DownloadedAndVerified(s) => s,
-            DownloadStatus::AlreadyInCache(s) => s,
+        .filter(|dependency| Receipt::current(&dependency.app).is_err())
+        .try_for_each(|dependency| {
+            println!("Installing dependency {} for {}", dependency.app, app_name);
+            install_into(
+                tx,
+                &dependency.app,
+                &dependency.bucket,
+                &dependency.manifest,
+                scripts,
+            )
         })
-        .map(|s| cache_dir.join(s))
-        .collect::<Vec<_>>();
-
-    srcs.iter().for_each(|s| {
-        let ar_type = FileKind::infer(s);
-
-        println!("{:?}", ar_type);
-    })
-
-    // let version = &manifest.version;
-
-    // let app_dir = Config::app_dir().unwrap();
-    // let app_dir = app_dir.join(app_name);
-    // let app_dir = app_dir.join(version);
-
-    // if !app_dir.exists() {
-    //     PathBuf::create(app_dir.clone()).unwrap();
-    //     use sevenz_rust::decompress_file;
-    //     for src in srcs {
-    //         if src
-    //             .extension()
-    //             .unwrap_or_default()
-    //             .to_string_lossy()
-    //             .to_string()
-    //             == "7z"
-    //         {
-    //             println!("decompressingn {:?}", src);
-    //             decompress_file(src, &app_dir).unwrap();
-    //         }
-    //     }
-    // }
-
-    // println!("{app_name}\n{manifest:#?}\n{file_name:#?} at {app_dir:?}");
+}
+
+/// Installs an already resolved manifest as a new version of `app_name` and makes it current.
+/// How to undo every step is recorded on `tx`, so that several installs can be rolled back together.
+fn install_into(
+    tx: &mut Transaction,
+    app_name: &str,
+    bucket: &str,
+    manifest: &Manifest,
+    scripts: &dyn ScriptRunner,
+) -> Result<(), ScoopieError> {
+    let version = manifest.version.installed();
+    let app_dir = Config::app_dir()?.join(app_name);
+    let version_dir = app_dir.join(version.as_str());
+
+    if version_dir.exists() {
+        return Err(ScoopieError::AppAlreadyInstalled(
+            app_name.into(),
+            version.to_string(),
+        ));
+    }
+
+    let downloads = Downloader::fetch(app_name, manifest, true)?;
+
+    if !app_dir.exists() {
+        let app_dir = app_dir.clone();
+        tx.on_rollback(move || app_dir.rm());
+    }
+
+    // Extracted next to its final location, so that moving it in place is a single rename.
+    let staging_dir = app_dir.join(format!(".{version}.staging"));
+
+    if staging_dir.exists() {
+        staging_dir.rm()?;
+    }
+
+    PathBuf::create(&staging_dir)?;
+
+    let staged = staging_dir.clone();
+    tx.on_rollback(move || match staged.exists() {
+        true => staged.rm(),
+        false => Ok(()),
+    });
+
+    extract(app_name, manifest, downloads, &staging_dir)?;
+
+    fs::rename(&staging_dir, &version_dir)
+        .map_err(|_| ScoopieError::FailedToMove(staging_dir.clone(), version_dir.clone()))?;
+
+    let installed = version_dir.clone();
+    tx.on_rollback(move || installed.rm());
+
+    let log = version_dir.join("install.log");
+
+    Hooks::run(
+        Hook::PreInstall,
+        app_name,
+        &version_dir,
+        manifest,
+        scripts,
+        Some(&log),
+    )?;
+
+    let default_file = manifest.url().last().map(target_name).unwrap_or_default();
+    Installers::install(
+        app_name,
+        &version_dir,
+        &default_file,
+        manifest,
+        scripts,
+        &log,
+    )?;
+
+    let persist_dir = Config::persist_dir()?.join(app_name);
+
+    if !persist_dir.exists() {
+        tx.on_rollback(move || match persist_dir.exists() {
+            true => persist_dir.rm(),
+            false => Ok(()),
+        });
+    }
+
+    let persisted = Persists::link(app_name, &version_dir, &manifest.persist())?;
+    tx.on_rollback(move || {
+        persisted
+            .iter()
+            .filter(|path| path.exists())
+            .try_for_each(|path| path.rm())
+    });
+
+    // Kept as the bucket has it, unless the bucket moved on since it was synced.
+    let manifest_path = version_dir.join("manifest.json");
+    let content = match Buckets::raw_manifest(bucket, app_name) {
+        Ok(raw)
+            if Manifest::try_from(raw.as_slice())
+                .is_ok_and(|raw| raw.version.as_str() == manifest.version.as_str()) =>
+        {
+            raw
+        }
+        _ => serde_json::to_vec_pretty(manifest)
+            .map_err(|_| ScoopieError::FailedToWriteFile(manifest_path.clone()))?,
+    };
+    fs::write(&manifest_path, content)
+        .map_err(|_| ScoopieError::FailedToWriteFile(manifest_path))?;
+
+    let commit_id = MetaData::read()?.get(bucket).commit_id;
+    let mut receipt = Receipt::new(app_name, bucket, &commit_id, manifest)?;
+    receipt.version = version;
+
+    activate(tx, app_name, &mut receipt, &version_dir, manifest)?;
+
+    Hooks::run(
+        Hook::PostInstall,
+        app_name,
+        &app_dir.join("current"),
+        manifest,
+        scripts,
+        Some(&log),
+    )
+}
+
+/// Extracts (or copies) every downloaded file of `manifest` into `dir`.
+fn extract(
+    app_name: &str,
+    manifest: &Manifest,
+    downloads: Vec<DownloadStatus>,
+    dir: &Path,
+) -> Result<(), ScoopieError> {
+    let cache_dir = Config::cache_dir()?;
+    let (extract_dirs, extract_tos) = extract_paths(app_name, manifest)?;
+
+    zip(manifest.url(), downloads)
+        .enumerate()
+        .try_for_each(|(idx, (url, status))| {
+            let src = cache_dir.join(status.file_name());
+            let name = target_name(&url);
+            inside(app_name, &name)?;
+
+            let kind = match FileKind::infer(&src)? {
+                FileKind::ExeFile if manifest.innosetup() => FileKind::InnoFile,
+                kind => kind,
+            };
+
+            let dest = match extract_tos.get(idx) {
+                Some(extract_to) => dir.join(extract_to),
+                None => dir.to_path_buf(),
+            };
+
+            match extract_dirs.get(idx) {
+                Some(extract_dir) if kind.is_archive() => {
+                    let staging = dir.join("_tmp");
+
+                    Extractor::extract(&src, &name, &staging, kind)?;
+                    hoist(&staging.join(extract_dir), &dest)?;
+
+                    staging.rm()
+                }
+                _ => Extractor::extract(&src, &name, &dest, kind),
+            }
+        })
+}
+
+/// `extract_dir` and `extract_to` of `manifest`, checked before anything gets extracted.
+fn extract_paths(
+    app_name: &str,
+    manifest: &Manifest,
+) -> Result<(Vec<PathBuf>, Vec<PathBuf>), ScoopieError> {
+    let checked = |paths: Vec<String>| {
+        paths
+            .iter()
+            .map(|path| inside(app_name, path))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    Ok((
+        checked(manifest.extract_dir())?,
+        checked(manifest.extract_to())?,
+    ))
+}
+
+/// `path` of the manifest of `app` as resolved by `contained`, manifests don't get to touch
+/// anything outside of the app's own directories.
+fn inside(app: &str, path: &str) -> Result<PathBuf, ScoopieError> {
+    contained(path).ok_or_else(|| ScoopieError::PathOutsideApp(app.into(), path.into()))
+}
+
+/// Makes `version_dir` the current version of `app`, moving shims and environment changes over
+/// from the previously current version (if any) and recording them in its receipt.
+fn activate(
+    tx: &mut Transaction,
+    app: &str,
+    receipt: &mut Receipt,
+    version_dir: &Path,
+    manifest: &Manifest,
+) -> Result<(), ScoopieError> {
+    let home_dir = Config::home_dir()?;
+    let app_dir = Config::app_dir()?.join(app);
+    let current_dir = app_dir.join("current");
+    let store = user_env(&home_dir);
+
+    Shims::check(&current_dir, &manifest.bin())?;
+
+    let previous = Receipt::current(app).ok().map(|previous| {
+        let previous_dir = app_dir.join(previous.version.as_str());
+        let bins = Manifest::try_from(previous_dir.join("manifest.json"))
+            .map(|manifest| manifest.bin())
+            .unwrap_or_default();
+
+        (previous, previous_dir, bins)
+    });
+
+    if let Some((previous, _, _)) = &previous {
+        previous.env.revert(store.as_ref())?;
+
+        let (env, home_dir) = (previous.env.clone(), home_dir.clone());
+        tx.on_rollback(move || env.restore(user_env(&home_dir).as_ref()));
+    }
+
+    // Registered before the steps they undo, those may fail halfway through.
+    {
+        let current_dir = current_dir.clone();
+        let previous = previous
+            .as_ref()
+            .map(|(_, previous_dir, bins)| (previous_dir.clone(), bins.clone()));
+        let app = app.to_string();
+
+        tx.on_rollback(move || {
+            Shims::remove(&app)?;
+
+            match previous {
+                Some((previous_dir, bins)) => {
+                    current_dir.link_to(&previous_dir)?;
+                    Shims::create(&current_dir, &bins)
+                }
+                None => match current_dir.symlink_metadata().is_ok() {
+                    true => current_dir.unlink(),
+                    false => Ok(()),
+                },
+            }
+        });
+    }
+
+    Shims::remove(app)?;
+    current_dir.link_to(version_dir)?;
+
+    let original = receipt.clone();
+    receipt.env = EnvChanges::apply(app, &current_dir, manifest, store.as_ref())?;
+
+    let (env, dir) = (receipt.env.clone(), version_dir.to_path_buf());
+    tx.on_rollback(move || {
+        env.revert(user_env(&home_dir).as_ref())?;
+        original.write(&dir)
+    });
+
+    receipt.write(version_dir)?;
+
+    Shims::create(&current_dir, &manifest.bin())
+}
+
+/// Points `apps/<app>/current` at an already installed version and regenerates its shims and environment.
+pub fn switch(app: &str, version: &str) -> Result<(), ScoopieError> {
+    let app_name = app.trim().to_lowercase();
+    let app_dir = Config::app_dir()?.join(&app_name);
+
+    if !app_dir.exists() {
+        return Err(ScoopieError::AppNotInstalled(app_name));
+    }
+
+    // Only an actual version directory will do, not `current`, a staging dir or another path.
+    let valid = !version.is_empty()
+        && version != "current"
+        && !version.starts_with('.')
+        && !version.contains(['/', '\\', ':']);
+
+    let receipt = match valid {
+        true => Receipt::read(&app_name, version).ok(),
+        false => None,
+    };
+
+    let mut receipt = receipt
+        .filter(|receipt| receipt.app == app_name && receipt.version.as_str() == version)
+        .ok_or_else(|| ScoopieError::VersionNotInstalled(app_name.clone(), version.into()))?;
+
+    let version_dir = app_dir.join(version);
+    let manifest = Manifest::try_from(version_dir.join("manifest.json"))?;
+
+    Transaction::run(|tx| activate(tx, &app_name, &mut receipt, &version_dir, &manifest))
+}
+
+/// Removes every installed version of `app`, refusing to do so while other apps depend on it unless `force`d.
+/// Persisted data is kept for a later reinstall unless `purge` is set.
+pub fn uninstall(
+    app: &str,
+    force: bool,
+    purge: bool,
+    scripts: &dyn ScriptRunner,
+) -> Result<(), ScoopieError> {
+    let app_name = app.trim().to_lowercase();
+    let app_dir = Config::app_dir()?.join(&app_name);
+
+    if !app_dir.exists() {
+        return Err(ScoopieError::AppNotInstalled(app_name));
+    }
+
+    let dependents = dependents(&app_name)?;
+
+    if !force && !dependents.is_empty() {
+        return Err(ScoopieError::RequiredBy(app_name, dependents.join(", ")));
+    }
+
+    let receipt = Receipt::current(&app_name)?;
+    println!(
+        "Uninstalling {} (v{}) from bucket {}",
+        receipt.app, receipt.version, receipt.bucket
+    );
+
+    let version_dir = app_dir.join(receipt.version.as_str());
+    let manifest = Manifest::try_from(version_dir.join("manifest.json")).ok();
+
+    if let Some(manifest) = &manifest {
+        Hooks::run(
+            Hook::PreUninstall,
+            &app_name,
+            &version_dir,
+            manifest,
+            scripts,
+            None,
+        )?;
+
+        Installers::uninstall(&app_name, &version_dir, manifest, scripts)?;
+    }
+
+    receipt
+        .env
+        .revert(user_env(&Config::home_dir()?).as_ref())?;
+    Shims::remove(&app_name)?;
+
+    // Scripts commonly clean up after files of `$dir`, so it has to still be around.
+    if let Some(manifest) = &manifest {
+        Hooks::run(
+            Hook::PostUninstall,
+            &app_name,
+            &version_dir,
+            manifest,
+            scripts,
+            None,
+        )?;
+    }
+
+    app_dir.rm()?;
+
+    if purge && Persists::purge(&app_name)? {
+        println!("Removed persisted data of {app_name}");
+    }
+
+    Ok(())
+}
+
+/// Moves everything inside `from` into `to`, merging with whatever `to` already contains.
+fn hoist(from: &Path, to: &Path) -> Result<(), ScoopieError> {
+    if !from.is_dir() {
+        return Err(ScoopieError::FileNotExist(from.into()));
+    }
+
+    PathBuf::create(&to.to_path_buf())?;
+
+    fs::read_dir(from)
+        .map_err(|_| ScoopieError::FailedToReadFile(from.into()))?
+        .filter_map(Result::ok)
+        .try_for_each(|entry| {
+            let target = to.join(entry.file_name());
+
+            match (entry.path().is_dir(), target.is_dir()) {
+                (true, true) => hoist(&entry.path(), &target),
+                _ => fs::rename(entry.path(), &target)
+                    .map_err(|_| ScoopieError::FailedToMove(entry.path(), target)),
+            }
+        })
+}
+
+/// Name under which a downloaded file lands in the app directory, manifests may rename
+/// downloads through the url fragment (e.g. `https://example.com/setup.exe#/dl.7z`).
+fn target_name(url: &Url) -> String {
+    match url.fragment().and_then(|f| f.strip_prefix('/')) {
+        Some(name) => name.into(),
+        None => url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+            .into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_paths_stay_inside() {
+        let manifest =
+            test_manifest(r#""extract_dir": "foo-1.2", "extract_to": ["bin", "", "lib\\x"]"#);
+        let (dirs, tos) = extract_paths("foo", &manifest).unwrap();
+
+        assert_eq!(dirs, vec![PathBuf::from("foo-1.2")]);
+        assert_eq!(
+            tos,
+            vec![
+                PathBuf::from("bin"),
+                PathBuf::new(),
+                PathBuf::from("lib").join("x")
+            ]
+        );
+
+        [
+            r#""extract_dir": "../../..""#,
+            r#""extract_dir": "C:\\Users""#,
+            r#""extract_to": ["bin", "/etc"]"#,
+            r#""extract_to": "lib/../../x""#,
+        ]
+        .iter()
+        .for_each(|fields| {
+            assert!(
+                matches!(
+                    extract_paths("foo", &test_manifest(fields)),
+                    Err(ScoopieError::PathOutsideApp(..))
+                ),
+                "{fields} was accepted"
+            )
+        });
+    }
 }
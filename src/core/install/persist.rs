@@ -0,0 +1,116 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::inside;
+use crate::core::{buckets::Persist, config::*};
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+/// Data listed under a manifest's `persist`, stored in `persists/<app>/` and linked back into
+/// every installed version so that user settings survive updates and reinstalls.
+pub struct Persists;
+
+impl Persists {
+    /// Links each persisted entry of `version_dir` to `persists/<app>/`, the first install of an
+    /// app moves the shipped data there while later ones keep what's already persisted.
+    /// Returns the entries that were newly created in `persists/<app>/`.
+    pub fn link(
+        app: &str,
+        version_dir: &Path,
+        entries: &[Persist],
+    ) -> Result<Vec<PathBuf>, ScoopieError> {
+        let persist_dir = Config::persist_dir()?.join(app);
+        let mut created = vec![];
+
+        // Checked upfront, so that a bad entry doesn't leave the others half linked.
+        let entries = entries
+            .iter()
+            .map(|entry| {
+                Ok((
+                    Self::inside(app, &entry.source)?,
+                    Self::inside(app, &entry.target)?,
+                ))
+            })
+            .collect::<Result<Vec<_>, ScoopieError>>()?;
+
+        entries.iter().try_for_each(|(source, target)| {
+            let source = version_dir.join(source);
+            let target = persist_dir.join(target);
+
+            if let Some(parent) = target.parent() {
+                PathBuf::create(&parent.to_path_buf())?;
+            }
+
+            if !target.exists() {
+                created.push(target.clone());
+            }
+
+            match (target.exists(), source.exists()) {
+                // Keep the freshly shipped copy around (like Scoop does) but use the persisted one.
+                (true, true) => {
+                    let original = PathBuf::from(format!("{}.original", source.display()));
+                    fs::rename(&source, &original)
+                        .map_err(|_| ScoopieError::FailedToMove(source.clone(), original))?;
+                }
+                (false, true) => fs::rename(&source, &target)
+                    .map_err(|_| ScoopieError::FailedToMove(source.clone(), target.clone()))?,
+                // Nothing shipped nor persisted yet, seed an empty directory for the app to fill.
+                (false, false) => PathBuf::create(&target)?,
+                (true, false) => {}
+            }
+
+            if let Some(parent) = source.parent() {
+                PathBuf::create(&parent.to_path_buf())?;
+            }
+
+            source.link_to(&target)
+        })?;
+
+        Ok(created)
+    }
+
+    /// Same as `inside`, but the whole app directory can't be persisted either.
+    fn inside(app: &str, path: &str) -> Result<PathBuf, ScoopieError> {
+        match inside(app, path)? {
+            entry if entry.as_os_str().is_empty() => {
+                Err(ScoopieError::PathOutsideApp(app.into(), path.into()))
+            }
+            entry => Ok(entry),
+        }
+    }
+
+    /// Deletes everything persisted for `app`, returns whether there was anything to delete.
+    pub fn purge(app: &str) -> Result<bool, ScoopieError> {
+        let persist_dir = Config::persist_dir()?.join(app);
+
+        match persist_dir.exists() {
+            true => persist_dir.rm().map(|_| true),
+            false => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn persisted_paths_stay_inside() {
+        assert_eq!(
+            Persists::inside("foo", "data\\config.ini").unwrap(),
+            PathBuf::from("data").join("config.ini")
+        );
+
+        ["", ".", "/etc", "data/../../x"].iter().for_each(|path| {
+            assert!(
+                matches!(
+                    Persists::inside("foo", path),
+                    Err(ScoopieError::PathOutsideApp(..))
+                ),
+                "{path:?} was accepted"
+            )
+        });
+    }
+}
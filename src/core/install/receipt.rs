@@ -0,0 +1,167 @@
+use std::{
+    fs,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use console::style;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use super::EnvChanges;
+use crate::core::{
+    buckets::{Manifest, Version},
+    config::*,
+    download::Hash,
+};
+use crate::error::ScoopieError;
+use crate::utils::AtomicWrite;
+
+/// Written as `install.json` into every installed version, records exactly which manifest revision
+/// produced the install so that later commands never have to consult the buckets for it.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Receipt {
+    pub app: String,
+    pub version: Version,
+    pub bucket: String,
+    pub commit_id: String,
+    pub architecture: Arch,
+    pub urls: Vec<Url>,
+    pub hashes: Vec<Hash>,
+    pub installed_at: u64,
+    #[serde(default)]
+    pub held: bool,
+    #[serde(default)]
+    pub env: EnvChanges,
+}
+
+impl Receipt {
+    pub fn new(
+        app: &str,
+        bucket: &str,
+        commit_id: &str,
+        manifest: &Manifest,
+    ) -> Result<Self, ScoopieError> {
+        let installed_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Ok(Self {
+            app: app.into(),
+            version: manifest.version.clone(),
+            bucket: bucket.into(),
+            commit_id: commit_id.into(),
+            architecture: Config::arch()?,
+            urls: manifest.url(),
+            hashes: manifest.hash(),
+            installed_at,
+            held: false,
+            env: EnvChanges::default(),
+        })
+    }
+
+    pub fn write(&self, version_dir: &Path) -> Result<(), ScoopieError> {
+        let receipt_path = version_dir.join("install.json");
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|_| ScoopieError::FailedToWriteFile(receipt_path.clone()))?;
+
+        receipt_path.write_atomic(content.as_bytes())
+    }
+
+    pub fn read(app: &str, version: &str) -> Result<Self, ScoopieError> {
+        let receipt_path = Config::app_dir()?
+            .join(app)
+            .join(version)
+            .join("install.json");
+
+        if !receipt_path.exists() {
+            return Err(ScoopieError::VersionNotInstalled(
+                app.into(),
+                version.into(),
+            ));
+        }
+
+        let content = fs::read_to_string(&receipt_path)
+            .map_err(|_| ScoopieError::FailedToReadFile(receipt_path.clone()))?;
+
+        serde_json::from_str(&content).map_err(|_| ScoopieError::InvalidReceipt(receipt_path))
+    }
+
+    /// Receipts of the active version of every installed app, sorted by app name.
+    pub fn installed() -> Result<Vec<Self>, ScoopieError> {
+        let apps_dir = Config::app_dir()?;
+
+        let mut receipts = fs::read_dir(&apps_dir)
+            .map_err(|_| ScoopieError::FailedToReadFile(apps_dir))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let app = entry.file_name().to_string_lossy().to_string();
+
+                match Self::current(&app) {
+                    Ok(receipt) => Some(receipt),
+                    // Not installed (anymore), e.g. what's left of an interrupted uninstall.
+                    Err(ScoopieError::AppNotInstalled(_)) => None,
+                    Err(err) => Self::skip(&app, err),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        receipts.sort_by(|a, b| a.app.cmp(&b.app));
+
+        Ok(receipts)
+    }
+
+    /// Receipts of every installed version of `app`, current or not.
+    pub fn versions(app: &str) -> Result<Vec<Self>, ScoopieError> {
+        let app_dir = Config::app_dir()?.join(app);
+
+        if !app_dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let receipts = fs::read_dir(&app_dir)
+            .map_err(|_| ScoopieError::FailedToReadFile(app_dir))?
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_name() != "current")
+            .filter_map(|entry| {
+                let version = entry.file_name().to_string_lossy().to_string();
+
+                match Self::read(app, &version) {
+                    Ok(receipt) => Some(receipt),
+                    // Not an installed version, e.g. the staging dir of an install in progress.
+                    Err(ScoopieError::VersionNotInstalled(..)) => None,
+                    Err(err) => Self::skip(&format!("{app}@{version}"), err),
+                }
+            })
+            .collect();
+
+        Ok(receipts)
+    }
+
+    /// Marks the current version of `app` as held back from updates, or releases it.
+    pub fn hold(app: &str, held: bool) -> Result<Self, ScoopieError> {
+        let mut receipt = Self::current(app)?;
+        receipt.held = held;
+
+        let version_dir = Config::app_dir()?.join(app).join(receipt.version.as_str());
+        receipt.write(&version_dir)?;
+
+        Ok(receipt)
+    }
+
+    /// Receipt of the version `apps/<app>/current` points at.
+    pub fn current(app: &str) -> Result<Self, ScoopieError> {
+        Self::read(app, "current").map_err(|err| match err {
+            ScoopieError::VersionNotInstalled(app, _) => ScoopieError::AppNotInstalled(app),
+            err => err,
+        })
+    }
+
+    /// Broken receipts are left out of listings rather than failing them, but never silently.
+    fn skip(what: &str, err: ScoopieError) -> Option<Self> {
+        eprintln!("{}", style(format!("Skipping {what}: {err}")).yellow());
+        None
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::core::{buckets::Bin, config::*};
+use crate::error::ScoopieError;
+use crate::utils::*;
+
+/// Contents of a `.shim` file, the same format which Scoop's `shim.exe` understands:
+///
+/// ```text
+/// path = "C:\scoopie\apps\git\2.42.0\bin\git.exe"
+/// args = --no-pager
+/// ```
+#[derive(Debug)]
+pub struct ShimDescriptor {
+    pub path: PathBuf,
+    pub args: String,
+}
+
+impl TryFrom<PathBuf> for ShimDescriptor {
+    type Error = ScoopieError;
+
+    fn try_from(value: PathBuf) -> Result<Self, Self::Error> {
+        let content =
+            fs::read_to_string(&value).map_err(|_| ScoopieError::FailedToReadFile(value))?;
+
+        let field = |key: &str| {
+            content
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .find(|(k, _)| k.trim() == key)
+                .map(|(_, v)| v.trim().trim_matches('"').to_string())
+        };
+
+        Ok(Self {
+            path: PathBuf::from(field("path").unwrap_or_default()),
+            args: field("args").unwrap_or_default(),
+        })
+    }
+}
+
+impl ShimDescriptor {
+    fn write(&self, path: &Path) -> Result<(), ScoopieError> {
+        let mut content = format!("path = \"{}\"\n", self.path.display());
+
+        if !self.args.is_empty() {
+            content.push_str(&format!("args = {}\n", self.args));
+        }
+
+        fs::write(path, content).map_err(|_| ScoopieError::FailedToWriteFile(path.into()))
+    }
+}
+
+pub struct Shims;
+
+impl Shims {
+    /// Creates shims for every bin of an app, `app_dir` is the directory the bins are relative to.
+    pub fn create(app_dir: &Path, bins: &[Bin]) -> Result<(), ScoopieError> {
+        let shims_dir = Config::shims_dir()?;
+        let apps_dir = Config::app_dir()?;
+        let shim_exe = apps_dir.join("scoopie\\bin\\shim.exe");
+
+        bins.iter().try_for_each(|bin| {
+            let target = Self::target(app_dir, bin)?;
+
+            if !target.exists() {
+                return Err(ScoopieError::FileNotExist(target));
+            }
+
+            let shim = shims_dir.join(format!("{}.shim", bin.alias));
+            Self::check_owner(&apps_dir, &shim, &target, &bin.alias)?;
+
+            let descriptor = ShimDescriptor {
+                path: target,
+                args: bin.args.join(" "),
+            };
+
+            descriptor.write(&shim)?;
+
+            let cmd = shims_dir.join(format!("{}.cmd", bin.alias));
+            fs::write(&cmd, Self::cmd_wrapper(&descriptor))
+                .map_err(|_| ScoopieError::FailedToWriteFile(cmd))?;
+
+            let is_exe = descriptor.path.extension().is_some_and(|ext| ext == "exe");
+
+            match is_exe && shim_exe.exists() {
+                true => {
+                    let exe = shims_dir.join(format!("{}.exe", bin.alias));
+                    fs::copy(&shim_exe, &exe)
+                        .map(|_| ())
+                        .map_err(|_| ScoopieError::FailedToWriteFile(exe))
+                }
+                false => Ok(()),
+            }
+        })
+    }
+
+    /// Fails if a shim for any of `bins` belongs to an app other than the one in `app_dir`, so that
+    /// callers can find out before changing anything.
+    pub fn check(app_dir: &Path, bins: &[Bin]) -> Result<(), ScoopieError> {
+        let shims_dir = Config::shims_dir()?;
+        let apps_dir = Config::app_dir()?;
+
+        bins.iter().try_for_each(|bin| {
+            let target = Self::target(app_dir, bin)?;
+            let shim = shims_dir.join(format!("{}.shim", bin.alias));
+            Self::check_owner(&apps_dir, &shim, &target, &bin.alias)
+        })
+    }
+
+    /// The file `bin` runs, which has to be inside `app_dir`. Its alias names files in the shims
+    /// directory, so it has to be a plain file name.
+    fn target(app_dir: &Path, bin: &Bin) -> Result<PathBuf, ScoopieError> {
+        let alias = bin.alias.as_str();
+        let plain = !alias.is_empty() && !alias.contains(['/', '\\', ':']) && alias != "..";
+
+        match contained(&bin.path) {
+            Some(path) if plain && path.components().next().is_some() => Ok(app_dir.join(path)),
+            _ => Err(ScoopieError::InvalidBin(
+                bin.path.clone(),
+                bin.alias.clone(),
+            )),
+        }
+    }
+
+    /// Another app's shim is left alone, while one of the same app gets updated.
+    fn check_owner(
+        apps_dir: &Path,
+        shim: &Path,
+        target: &Path,
+        alias: &str,
+    ) -> Result<(), ScoopieError> {
+        let other = ShimDescriptor::try_from(shim.to_path_buf())
+            .ok()
+            .and_then(|existing| Self::owner(apps_dir, &existing.path))
+            .filter(|other| Self::owner(apps_dir, target).as_ref() != Some(other));
+
+        match other {
+            Some(other) => Err(ScoopieError::ShimConflict(alias.into(), other)),
+            None => Ok(()),
+        }
+    }
+
+    /// Resolves a shim to the app providing it and the real path of its target.
+    pub fn resolve(name: &str) -> Result<(String, PathBuf), ScoopieError> {
+        let apps_dir = Config::app_dir()?;
+        let alias = Path::new(name)
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        let shim = Config::shims_dir()?.join(format!("{alias}.shim"));
+
+        if !shim.exists() {
+            return Err(ScoopieError::UnableToLocate(name.into()));
+        }
+
+        let target = ShimDescriptor::try_from(shim)?.path;
+        let app = Self::owner(&apps_dir, &target)
+            .ok_or_else(|| ScoopieError::UnableToLocate(name.into()))?;
+
+        Ok((app, target.absolute()?))
+    }
+
+    /// The app whose directory `target` is in.
+    fn owner(apps_dir: &Path, target: &Path) -> Option<String> {
+        target
+            .strip_prefix(apps_dir)
+            .ok()
+            .and_then(|path| path.iter().next())
+            .map(|app| app.to_string_lossy().to_string())
+    }
+
+    /// Removes every shim whose target lives inside `apps/<app>`.
+    pub fn remove(app: &str) -> Result<(), ScoopieError> {
+        let shims_dir = Config::shims_dir()?;
+        let app_dir = Config::app_dir()?.join(app);
+
+        Self::owned_by(&shims_dir, &app_dir)?
+            .into_iter()
+            .flat_map(|alias| {
+                ["shim", "cmd", "exe"].map(|ext| shims_dir.join(format!("{alias}.{ext}")))
+            })
+            .filter(|file| file.exists())
+            .try_for_each(|file| file.rm())
+    }
+
+    fn owned_by(shims_dir: &Path, app_dir: &Path) -> Result<Vec<String>, ScoopieError> {
+        let shims = fs::read_dir(shims_dir)
+            .map_err(|_| ScoopieError::FailedToReadFile(shims_dir.into()))?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "shim"))
+            .filter_map(|path| {
+                let descriptor = ShimDescriptor::try_from(path.clone()).ok()?;
+
+                match descriptor.path.starts_with(app_dir) {
+                    true => Some(path.file_stem()?.to_string_lossy().to_string()),
+                    false => None,
+                }
+            })
+            .collect();
+
+        Ok(shims)
+    }
+
+    fn cmd_wrapper(descriptor: &ShimDescriptor) -> String {
+        let path = &descriptor.path;
+        let args = &descriptor.args;
+
+        let invocation = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("ps1") => format!(
+                "powershell -NoProfile -ExecutionPolicy Bypass -File \"{}\"",
+                path.display()
+            ),
+            Some("jar") => format!("java -jar \"{}\"", path.display()),
+            Some("py") => format!("python \"{}\"", path.display()),
+            _ => format!("\"{}\"", path.display()),
+        };
+
+        format!("@rem {}\r\n@{invocation} {args} %*\r\n", path.display())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bin(path: &str, alias: &str) -> Bin {
+        Bin {
+            path: path.into(),
+            alias: alias.into(),
+            args: vec![],
+        }
+    }
+
+    #[test]
+    fn bins_stay_inside_the_app() {
+        let app_dir = Path::new("apps/foo/current");
+
+        assert_eq!(
+            Shims::target(app_dir, &bin("bin\\foo.exe", "foo")).unwrap(),
+            app_dir.join("bin").join("foo.exe")
+        );
+        assert!(Shims::target(app_dir, &bin("./foo.exe", "foo.bar")).is_ok());
+
+        [
+            ("..\\..\\bar\\bar.exe", "bar"),
+            ("/bin/sh", "sh"),
+            ("C:\\Windows\\notepad.exe", "notepad"),
+            ("", "foo"),
+            ("foo.exe", "..\\foo"),
+            ("foo.exe", "../foo"),
+            ("foo.exe", "bin/foo"),
+            ("foo.exe", ".."),
+            ("foo.exe", ""),
+        ]
+        .iter()
+        .for_each(|(path, alias)| {
+            assert!(matches!(
+                Shims::target(app_dir, &bin(path, alias)),
+                Err(ScoopieError::InvalidBin(_, _))
+            ));
+        });
+    }
+}
@@ -0,0 +1,46 @@
+use crate::error::ScoopieError;
+
+type Undo = Box<dyn FnOnce() -> Result<(), ScoopieError>>;
+
+/// Records how to undo every completed step of a multi-step change, so that a failure midway
+/// can put everything back the way it was.
+#[derive(Default)]
+pub struct Transaction {
+    undo: Vec<Undo>,
+}
+
+impl Transaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `undo` to be run if the transaction is rolled back, most recent first.
+    pub fn on_rollback(&mut self, undo: impl FnOnce() -> Result<(), ScoopieError> + 'static) {
+        self.undo.push(Box::new(undo));
+    }
+
+    /// Runs `steps`, rolling back whatever they completed if they fail.
+    pub fn run<T>(
+        steps: impl FnOnce(&mut Self) -> Result<T, ScoopieError>,
+    ) -> Result<T, ScoopieError> {
+        let mut transaction = Self::new();
+
+        match steps(&mut transaction) {
+            Ok(value) => Ok(value),
+            Err(err) => {
+                transaction.rollback();
+                Err(err)
+            }
+        }
+    }
+
+    /// Undoes completed steps in reverse order, carrying on past the ones that fail to undo
+    /// since leaving less behind is still better than stopping.
+    pub fn rollback(self) {
+        self.undo.into_iter().rev().for_each(|undo| {
+            if let Err(err) = undo() {
+                eprintln!("Failed to roll back: {err}");
+            }
+        });
+    }
+}
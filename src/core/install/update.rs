@@ -0,0 +1,115 @@
+use std::fmt::{self, Display, Formatter};
+
+use super::{
+    install_depends, install_into, resolve_app, resolve_with_depends, switch, Hook, Hooks,
+    Installers, Receipt, ScriptRunner, Transaction,
+};
+
+use crate::core::buckets::{Buckets, Manifest, Query, Version};
+use crate::core::config::*;
+use crate::core::download::Downloader;
+use crate::error::ScoopieError;
+
+#[derive(Debug, PartialEq)]
+pub enum UpdateStatus {
+    UpToDate(String, Version),
+    Updated(String, Version, Version),
+    Held(String, Version),
+}
+
+impl Display for UpdateStatus {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            UpdateStatus::UpToDate(app, version) => {
+                write!(f, "{app} (v{version}) is already up-to-date")
+            }
+            UpdateStatus::Updated(app, from, to) => {
+                write!(f, "Updated {app} from v{from} to v{to}")
+            }
+            UpdateStatus::Held(app, version) => {
+                write!(f, "{app} is held at v{version}, skipping")
+            }
+        }
+    }
+}
+
+/// Upgrades `app` to the version offered by the bucket it was installed from.
+/// The old version's uninstall scripts run first and new dependencies are installed along with it.
+pub fn update(app: &str, scripts: &dyn ScriptRunner) -> Result<UpdateStatus, ScoopieError> {
+    let app_name = app.trim().to_lowercase();
+    let receipt = Receipt::current(&app_name)?;
+
+    if receipt.held {
+        return Ok(UpdateStatus::Held(app_name, receipt.version));
+    }
+
+    // Another bucket may ship an entirely different app under the same name.
+    let manifest = Buckets::query_app(&app_name)?
+        .get_app_from(&app_name, &receipt.bucket)
+        .ok_or(ScoopieError::NoAppFoundInBucket(
+            app_name.clone(),
+            receipt.bucket.clone(),
+        ))?;
+
+    // Nightlies are stamped with the day they were installed, so they update once a day at most.
+    if manifest.version <= receipt.version || manifest.version.installed() == receipt.version {
+        return Ok(UpdateStatus::UpToDate(app_name, receipt.version));
+    }
+
+    let version = manifest.version.installed();
+
+    // Still around from before a `switch` back to an older version, no need to install it again.
+    if Receipt::read(&app_name, version.as_str()).is_ok() {
+        switch(&app_name, version.as_str())?;
+
+        return Ok(UpdateStatus::Updated(
+            app_name,
+            receipt.version,
+            manifest.version,
+        ));
+    }
+
+    let mut order = resolve_with_depends(&format!("{}/{app_name}", receipt.bucket), &resolve_app)?;
+    order.pop();
+
+    // Fetched up front, a failing download shouldn't leave the old version uninstalled.
+    Downloader::fetch(&app_name, &manifest, true)?;
+
+    let old_dir = Config::app_dir()?
+        .join(&app_name)
+        .join(receipt.version.as_str());
+
+    if let Ok(old) = Manifest::try_from(old_dir.join("manifest.json")) {
+        Hooks::run(Hook::PreUninstall, &app_name, &old_dir, &old, scripts, None)?;
+        Installers::uninstall(&app_name, &old_dir, &old, scripts)?;
+        Hooks::run(
+            Hook::PostUninstall,
+            &app_name,
+            &old_dir,
+            &old,
+            scripts,
+            None,
+        )?;
+    }
+
+    Transaction::run(|tx| {
+        install_depends(tx, &app_name, &order, scripts)?;
+        install_into(tx, &app_name, &receipt.bucket, &manifest, scripts)
+    })?;
+
+    Ok(UpdateStatus::Updated(
+        app_name,
+        receipt.version,
+        manifest.version,
+    ))
+}
+
+/// Updates every installed app, a failing app doesn't stop the others from being updated.
+pub fn update_all(
+    scripts: &dyn ScriptRunner,
+) -> Result<Vec<Result<UpdateStatus, ScoopieError>>, ScoopieError> {
+    Ok(Receipt::installed()?
+        .iter()
+        .map(|receipt| update(&receipt.app, scripts))
+        .collect())
+}
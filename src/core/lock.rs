@@ -0,0 +1,130 @@
+use std::{
+    fs::{self, File, OpenOptions, TryLockError},
+    path::{Path, PathBuf},
+    process,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::config::*;
+use crate::error::ScoopieError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LockMode {
+    /// Any number of read-only operations may run together.
+    Shared,
+    /// Only one operation that changes `$SCOOPIE_HOME` may run, with nothing else alongside.
+    Exclusive,
+}
+
+/// Who holds the exclusive lock, kept next to the lock file since Windows doesn't allow reading
+/// a file locked by another process.
+#[derive(Debug, Deserialize, Serialize)]
+struct Holder {
+    pid: u32,
+    started_at: u64,
+}
+
+impl Holder {
+    fn describe(&self) -> String {
+        let elapsed = now().saturating_sub(self.started_at);
+        format!("PID {}, started {elapsed} secs ago", self.pid)
+    }
+}
+
+/// Lock on `$SCOOPIE_HOME/scoopie.lock` held for as long as a command runs. It's an OS level
+/// lock so it goes away with the process, even if that crashes.
+pub struct Lock {
+    file: File,
+    holder: Option<PathBuf>,
+}
+
+impl Lock {
+    /// Acquires the lock in `mode`, failing right away if another operation holds it, unless `wait`.
+    pub fn acquire(mode: LockMode, wait: bool) -> Result<Self, ScoopieError> {
+        let home_dir = Config::home_dir()?;
+        let lock_path = home_dir.join("scoopie.lock");
+        let holder_path = home_dir.join("scoopie.lock.json");
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .map_err(|_| ScoopieError::FailedToOpenFile(lock_path.clone()))?;
+
+        let locked = match mode {
+            LockMode::Shared => file.try_lock_shared(),
+            LockMode::Exclusive => file.try_lock(),
+        };
+
+        match locked {
+            Ok(()) => {}
+            Err(TryLockError::WouldBlock) => {
+                let holder = Self::holder(&holder_path)
+                    .map(|holder| holder.describe())
+                    .unwrap_or_else(|| String::from("another scoopie process"));
+
+                if !wait {
+                    return Err(ScoopieError::OperationInProgress(holder));
+                }
+
+                eprintln!("Waiting for another scoopie operation ({holder}) to finish...");
+
+                match mode {
+                    LockMode::Shared => file.lock_shared(),
+                    LockMode::Exclusive => file.lock(),
+                }
+                .map_err(|_| ScoopieError::FailedToOpenFile(lock_path))?;
+            }
+            Err(TryLockError::Error(_)) => return Err(ScoopieError::FailedToOpenFile(lock_path)),
+        }
+
+        if mode == LockMode::Shared {
+            return Ok(Self { file, holder: None });
+        }
+
+        // Holding the exclusive lock means whoever wrote this is gone without cleaning up.
+        if let Some(stale) = Self::holder(&holder_path) {
+            eprintln!("Recovered a stale lock ({})", stale.describe());
+        }
+
+        let holder = Holder {
+            pid: process::id(),
+            started_at: now(),
+        };
+
+        let content = serde_json::to_string(&holder)
+            .map_err(|_| ScoopieError::FailedToWriteFile(holder_path.clone()))?;
+        fs::write(&holder_path, content)
+            .map_err(|_| ScoopieError::FailedToWriteFile(holder_path.clone()))?;
+
+        Ok(Self {
+            file,
+            holder: Some(holder_path),
+        })
+    }
+
+    fn holder(path: &Path) -> Option<Holder> {
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Some(holder) = &self.holder {
+            let _ = fs::remove_file(holder);
+        }
+
+        let _ = self.file.unlock();
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
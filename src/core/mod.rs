@@ -2,3 +2,4 @@ pub mod buckets;
 pub mod config;
 pub mod download;
 pub mod install;
+pub mod lock;
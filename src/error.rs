@@ -14,7 +14,12 @@ pub enum ScoopieError {
     // Bucket related errors
     BucketsNotFound,
     FailedToReadBucket(String),
+    InvalidMetadata(PathBuf),
     InvalidManifestInBucket,
+    BucketAlreadyExists(String),
+    UnknownBucket(String),
+    InvalidBucketName(String),
+    BucketUrlRequired(String),
 
     // Init related errors
     ConfigWriteWhileInit,
@@ -41,11 +46,28 @@ pub enum ScoopieError {
     // Query Errors
     InvalidRegex(String),
 
+    // Install related errors
+    AppAlreadyInstalled(String, String),
+    FailedToExtract(PathBuf),
+    FailedToMove(PathBuf, PathBuf),
+    AppNotInstalled(String),
+    VersionNotInstalled(String, String),
+    VersionRequired(String),
+    InvalidReceipt(PathBuf),
+    UnableToLocate(String),
+    DependencyCycle(String),
+    HookFailed(String, String),
+    RequiredBy(String, String),
+    FailedToLink(PathBuf, PathBuf),
+    OperationInProgress(String),
+    PathOutsideApp(String, String),
+    ShimConflict(String, String),
+    InvalidBin(String, String),
+
     // Command Errors
     UnableToExecuteCmd,
 
     // Common Errors
-    UnableToMkTmpDir,
     UserDirUnavailable,
     HomeDirUnavailable,
     CacheDirUnavailable,
@@ -62,10 +84,9 @@ pub enum ScoopieError {
     FileNotExist(PathBuf),
     FailedToOpenFile(PathBuf),
     FailedToReadFile(PathBuf),
+    FailedToWriteFile(PathBuf),
     FailedToGetMetadata(PathBuf),
-    UnableToOpenEnvRegistry,
     UnsupportedArch,
-    UnableToGetEnvVar(String),
     NonUTF8Bytes,
     Unknown,
 }
@@ -95,7 +116,12 @@ impl Display for ScoopieError {
             ScoopieError::FailedToReadBucket(bucket) => {
                 write!(f, "Failed to read bucket: {bucket}")
             }
+            ScoopieError::InvalidMetadata(path) => write!(f, "Buckets metadata at \"{}\" is corrupted. Hint: Run \"scoopie rm -b\" and then \"scoopie install -S\" to sync the buckets again", path.display()),
             ScoopieError::InvalidManifestInBucket => write!(f, "Invalid JSON format"),
+            ScoopieError::BucketAlreadyExists(bucket) => write!(f, "Bucket \"{bucket}\" already exists. Hint: Remove it first with \"scoopie bucket rm {bucket}\""),
+            ScoopieError::InvalidBucketName(bucket) => write!(f, "\"{bucket}\" can't be used as a bucket name, it must be made of letters, digits, \"-\", \"_\" or \".\", not start with \".\" and not be \"metadata.json\""),
+            ScoopieError::BucketUrlRequired(bucket) => write!(f, "\"{bucket}\" is not a known bucket, so its url is required. Hint: Run \"scoopie bucket known\" to see known buckets"),
+            ScoopieError::UnknownBucket(bucket) => write!(f, "No bucket named \"{bucket}\" is configured. Hint: Run \"scoopie bucket list\" to see configured buckets"),
 
             // Init related errors
             ScoopieError::ConfigWriteWhileInit => write!(f, "Config write error"),
@@ -148,10 +174,27 @@ impl Display for ScoopieError {
             
             // Query Errors            
             ScoopieError::InvalidRegex(pat) => write!(f, "Query failed due to invalid regex pattern: \"{pat}\""),
+
+            // Install related errors
+            ScoopieError::AppAlreadyInstalled(app, version) => write!(f, "\"{app}\" (v{version}) is already installed"),
+            ScoopieError::FailedToExtract(file) => write!(f, "Failed to extract: \"{}\"", file.display()),
+            ScoopieError::AppNotInstalled(app) => write!(f, "\"{app}\" is not installed"),
+            ScoopieError::VersionNotInstalled(app, version) => write!(f, "\"{app}\" (v{version}) is not installed"),
+            ScoopieError::VersionRequired(app) => write!(f, "No version specified for \"{app}\". Hint: Use \"<app>@<version>\""),
+            ScoopieError::InvalidReceipt(receipt) => write!(f, "Install receipt: \"{}\" is corrupted", receipt.display()),
+            ScoopieError::UnableToLocate(name) => write!(f, "Unable to locate: \"{name}\" as neither an installed app nor a binary provided by one"),
+            ScoopieError::HookFailed(app, hook) => write!(f, "The \"{hook}\" script of \"{app}\" failed, see its install.log for details. Hint: Use \"--no-scripts\" to skip manifest scripts"),
+            ScoopieError::DependencyCycle(cycle) => write!(f, "Circular dependency detected: {cycle}"),
+            ScoopieError::RequiredBy(app, dependents) => write!(f, "Unable to uninstall \"{app}\" as it is required by: {dependents}. Hint: Use \"--force\" to uninstall anyway"),
+            ScoopieError::OperationInProgress(holder) => write!(f, "Another scoopie operation is in progress ({holder}). Hint: Use \"--wait\" to wait for it to finish"),
+            ScoopieError::PathOutsideApp(app, path) => write!(f, "The manifest of \"{app}\" refers to \"{path}\", only paths inside the app's directory can be used"),
+            ScoopieError::ShimConflict(alias, app) => write!(f, "\"{alias}\" is already provided by \"{app}\". Hint: Uninstall \"{app}\" first to use this one instead"),
+            ScoopieError::InvalidBin(path, alias) => write!(f, "Can't shim \"{path}\" as \"{alias}\", only files inside the app's directory can be shimmed under a plain name"),
+            ScoopieError::FailedToLink(link, target) => write!(f, "Failed to link \"{}\" to \"{}\"", link.display(), target.display()),
+            ScoopieError::FailedToMove(from, to) => write!(f, "Failed to move \"{}\" to \"{}\"", from.display(), to.display()),
             
 
             // Common Errors
-            ScoopieError::UnableToMkTmpDir => write!(f, "Failed to make temporary directory"),
             ScoopieError::UserDirUnavailable => write!(f, "Failed to retrieve current user directory"),
             ScoopieError::HomeDirUnavailable => write!(f, "Failed to retrieve scoopie home directory. Hint: Check if $SCOOPIE_HOME is set correctly and it is properly initialized, if not then first run \"scoopie nuke\" and then \"scoopie init <your_desired_directory>\""),
             ScoopieError::CacheDirUnavailable => write!(f, "Failed to retrieve scoopie cache directory. Hint: Check if $SCOOPIE_HOME is set correctly and it is properly initialized, if not then first run \"scoopie nuke\" and then \"scoopie init <your_desired_directory>\""),
@@ -168,10 +211,9 @@ impl Display for ScoopieError {
             ScoopieError::FileNotExist(file) => write!(f, "Failed as file: \"{}\" not found", file.display()),
             ScoopieError::FailedToOpenFile(file) => write!(f, "Failed to open file: \"{}\"", file.display()),
             ScoopieError::FailedToReadFile(file) => write!(f, "Failed to read file: \"{}\"", file.display()),
+            ScoopieError::FailedToWriteFile(file) => write!(f, "Failed to write file: \"{}\"", file.display()),
             ScoopieError::FailedToGetMetadata(file) => write!(f, "Failed to get metadata of file: \"{}\"", file.display()),
-            ScoopieError::UnableToOpenEnvRegistry => write!(f, "Failed to open Environment Registry to perform environment variable function"),
             ScoopieError::UnsupportedArch => write!(f, "Failed as current architecture is not supported."),
-            ScoopieError::UnableToGetEnvVar(var) => write!(f, "Failed to get environment variable: \"{var}\" from current user's registry"),
             ScoopieError::Unknown => write!(f, "Unknow error occured"),
             ScoopieError::NonUTF8Bytes => write!(f, "Failed to convert to string due to invalid bytes"),
             ScoopieError::UnableToExecuteCmd => write!(f, "Unable to execute command")
@@ -10,8 +10,9 @@ fn main() {
     let start = std::time::Instant::now();
 
     match argh::from_env::<Commands>().exec() {
+        // Status goes to stderr so that stdout stays machine readable (e.g. `list --json`).
         Ok(_) => {
-            println!(
+            eprintln!(
                 "{} Done, took {} secs.",
                 Emoji("✨", ":)"),
                 start.elapsed().as_secs()
@@ -0,0 +1,212 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::ScoopieError;
+
+#[cfg(windows)]
+use super::Pwsh;
+
+/// Persistent (user level) environment variables, the ones new shells start with.
+pub trait EnvStore {
+    type Error;
+
+    fn get(&self, key: &str) -> Result<Option<String>, Self::Error>;
+    fn set(&self, key: &str, value: &str) -> Result<(), Self::Error>;
+    fn remove(&self, key: &str) -> Result<(), Self::Error>;
+
+    /// Prepends `dirs` to `PATH`, returns only the ones that weren't already part of it.
+    fn add_to_path(&self, dirs: &[PathBuf]) -> Result<Vec<PathBuf>, Self::Error> {
+        let mut paths = self.paths()?;
+
+        let added = dirs
+            .iter()
+            .filter(|dir| !paths.contains(dir))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if !added.is_empty() {
+            paths.splice(0..0, added.iter().cloned());
+            self.set_paths(&paths)?;
+        }
+
+        Ok(added)
+    }
+
+    fn remove_from_path(&self, dirs: &[PathBuf]) -> Result<(), Self::Error> {
+        let paths = self.paths()?;
+        let remaining = paths
+            .iter()
+            .filter(|path| !dirs.contains(path))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        match remaining.len() == paths.len() {
+            true => Ok(()),
+            false => self.set_paths(&remaining),
+        }
+    }
+
+    fn paths(&self) -> Result<Vec<PathBuf>, Self::Error> {
+        Ok(self
+            .get("PATH")?
+            .map(|path| {
+                // An empty entry (e.g. an emptied `PATH`) isn't a dir anything was added as.
+                env::split_paths(&path)
+                    .filter(|path| !path.as_os_str().is_empty())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    fn set_paths(&self, paths: &[PathBuf]) -> Result<(), Self::Error>;
+}
+
+/// Keeps user variables in `HKCU\Environment`, the same place Windows' own settings dialog does.
+#[cfg(windows)]
+pub struct RegistryEnv;
+
+#[cfg(windows)]
+impl RegistryEnv {
+    // Only variables set through the .NET API make running apps (e.g. Explorer) pick up changes,
+    // so a throwaway one is set and removed after writing to the registry directly.
+    const BROADCAST: &'static str = "[Environment]::SetEnvironmentVariable('SCOOPIE_REFRESH', '1', 'User'); [Environment]::SetEnvironmentVariable('SCOOPIE_REFRESH', $null, 'User')";
+}
+
+#[cfg(windows)]
+impl EnvStore for RegistryEnv {
+    type Error = ScoopieError;
+
+    fn get(&self, key: &str) -> Result<Option<String>, Self::Error> {
+        // Keep `%VAR%` references unexpanded so writing the value back doesn't bake them in.
+        let value = Pwsh::run(
+            None,
+            &format!(
+                "(Get-Item 'HKCU:\\Environment').GetValue({}, $null, 'DoNotExpandEnvironmentNames')",
+                Pwsh::quote(key)
+            ),
+        )
+        .map_err(|_| ScoopieError::EnvResolve)?;
+
+        let value = value.trim_end_matches(['\r', '\n']);
+
+        Ok((!value.is_empty()).then(|| value.to_string()))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), Self::Error> {
+        let kind = match value.contains('%') {
+            true => "ExpandString",
+            false => "String",
+        };
+
+        Pwsh::run(
+            None,
+            &format!(
+                "(Get-Item 'HKCU:\\').OpenSubKey('Environment', $true).SetValue({}, {}, '{kind}'); {}",
+                Pwsh::quote(key),
+                Pwsh::quote(value),
+                Self::BROADCAST
+            ),
+        )
+        .map(|_| ())
+        .map_err(|_| ScoopieError::EnvSet)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Self::Error> {
+        Pwsh::run(
+            None,
+            &format!(
+                "(Get-Item 'HKCU:\\').OpenSubKey('Environment', $true).DeleteValue({}, $false); {}",
+                Pwsh::quote(key),
+                Self::BROADCAST
+            ),
+        )
+        .map(|_| ())
+        .map_err(|_| ScoopieError::EnvRemove)
+    }
+
+    fn set_paths(&self, paths: &[PathBuf]) -> Result<(), Self::Error> {
+        // `env::join_paths` would reject entries containing `;` so the value is built by hand.
+        let paths = paths
+            .iter()
+            .map(|path| path.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(";");
+
+        self.set("PATH", &paths)
+    }
+}
+
+/// Keeps variables in a JSON file, used on hosts without a registry (and handy for testing).
+#[cfg_attr(windows, allow(dead_code))]
+pub struct FileEnv {
+    path: PathBuf,
+}
+
+#[cfg_attr(windows, allow(dead_code))]
+impl FileEnv {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    fn read(&self) -> Result<BTreeMap<String, String>, ScoopieError> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+
+        let content = fs::read_to_string(&self.path)
+            .map_err(|_| ScoopieError::FailedToReadFile(self.path.clone()))?;
+
+        serde_json::from_str(&content).map_err(|_| ScoopieError::EnvResolve)
+    }
+
+    fn write(&self, vars: &BTreeMap<String, String>) -> Result<(), ScoopieError> {
+        let content = serde_json::to_string_pretty(vars)
+            .map_err(|_| ScoopieError::FailedToWriteFile(self.path.clone()))?;
+
+        fs::write(&self.path, content)
+            .map_err(|_| ScoopieError::FailedToWriteFile(self.path.clone()))
+    }
+}
+
+impl EnvStore for FileEnv {
+    type Error = ScoopieError;
+
+    fn get(&self, key: &str) -> Result<Option<String>, Self::Error> {
+        Ok(self.read()?.remove(key))
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), Self::Error> {
+        let mut vars = self.read()?;
+        vars.insert(key.into(), value.into());
+        self.write(&vars).map_err(|_| ScoopieError::EnvSet)
+    }
+
+    fn remove(&self, key: &str) -> Result<(), Self::Error> {
+        let mut vars = self.read()?;
+
+        match vars.remove(key) {
+            Some(_) => self.write(&vars).map_err(|_| ScoopieError::EnvRemove),
+            None => Ok(()),
+        }
+    }
+
+    fn set_paths(&self, paths: &[PathBuf]) -> Result<(), Self::Error> {
+        let paths = env::join_paths(paths).map_err(|_| ScoopieError::EnvSet)?;
+        self.set("PATH", &paths.to_string_lossy())
+    }
+}
+
+/// The store user variables belong in on this host.
+#[cfg(windows)]
+pub fn user_env(_home_dir: &Path) -> Box<dyn EnvStore<Error = ScoopieError>> {
+    Box::new(RegistryEnv)
+}
+
+/// The store user variables belong in on this host, kept as `env.json` inside `home_dir`.
+#[cfg(not(windows))]
+pub fn user_env(home_dir: &Path) -> Box<dyn EnvStore<Error = ScoopieError>> {
+    Box::new(FileEnv::new(home_dir.join("env.json")))
+}
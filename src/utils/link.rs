@@ -0,0 +1,70 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::error::ScoopieError;
+
+#[cfg(windows)]
+use super::Pwsh;
+
+/// Directory and file links, backed by junctions and hard links on Windows (no elevation needed)
+/// and symlinks elsewhere.
+pub trait Link {
+    type Error;
+    fn link_to(&self, target: &Path) -> Result<(), Self::Error>;
+    fn unlink(&self) -> Result<(), Self::Error>;
+}
+
+impl Link for PathBuf {
+    type Error = ScoopieError;
+
+    fn link_to(&self, target: &Path) -> Result<(), Self::Error> {
+        if self.symlink_metadata().is_ok() {
+            self.unlink()?;
+        }
+
+        #[cfg(windows)]
+        let linked = match target.is_file() {
+            true => fs::hard_link(target, self).is_ok(),
+            false => Pwsh::run(
+                None,
+                &format!(
+                    "New-Item -ItemType Junction -Path {} -Target {} | Out-Null",
+                    Pwsh::quote(&self.to_string_lossy()),
+                    Pwsh::quote(&target.to_string_lossy())
+                ),
+            )
+            .is_ok(),
+        };
+
+        #[cfg(unix)]
+        let linked = std::os::unix::fs::symlink(target, self).is_ok();
+
+        match linked {
+            true => Ok(()),
+            false => Err(ScoopieError::FailedToLink(
+                self.to_path_buf(),
+                target.to_path_buf(),
+            )),
+        }
+    }
+
+    fn unlink(&self) -> Result<(), Self::Error> {
+        // Junctions are directories as far as Windows is concerned, symlinks are files on unix.
+        #[cfg(windows)]
+        let unlinked = match self.is_dir() {
+            true => fs::remove_dir(self),
+            false => fs::remove_file(self),
+        };
+
+        #[cfg(unix)]
+        let unlinked = fs::remove_file(self);
+
+        unlinked.map_err(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => ScoopieError::FileNotExist(self.to_path_buf()),
+            std::io::ErrorKind::PermissionDenied => ScoopieError::PermissionDenied,
+            _ => ScoopieError::Unknown,
+        })
+    }
+}
@@ -1,14 +1,25 @@
+mod env;
+mod link;
 mod pwsh;
-mod tmpdir;
+#[cfg(test)]
+mod testing;
 
 use crate::error::ScoopieError;
 use std::{
-    fs::{remove_dir_all, remove_file, DirBuilder},
+    fs::{self, remove_dir_all, remove_file, DirBuilder, File},
+    io::Write,
     path::PathBuf,
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
 };
 
+#[cfg(test)]
+pub use env::FileEnv;
+pub use env::{user_env, EnvStore};
+pub use link::Link;
 pub use pwsh::Pwsh;
-pub use tmpdir::TempDir;
+#[cfg(test)]
+pub use testing::*;
 
 #[macro_export]
 macro_rules! comptime_regex {
@@ -28,7 +39,7 @@ impl Remove for PathBuf {
     fn rm(&self) -> Result<(), Self::Error> {
         match self.is_file() {
             true => remove_file(self),
-            false => remove_dir_all(&self),
+            false => remove_dir_all(self),
         }
         .map_err(|err| match err.kind() {
             std::io::ErrorKind::NotFound => ScoopieError::FileNotExist(self.to_path_buf()),
@@ -38,6 +49,45 @@ impl Remove for PathBuf {
     }
 }
 
+pub trait AtomicWrite {
+    type Error;
+    fn write_atomic(&self, contents: &[u8]) -> Result<(), Self::Error>;
+}
+
+impl AtomicWrite for PathBuf {
+    type Error = ScoopieError;
+
+    /// Writes `contents` to a temporary file next to `self`, syncs it to disk and renames it over
+    /// `self`, so that readers see either the old or the new file but never a partial one.
+    fn write_atomic(&self, contents: &[u8]) -> Result<(), Self::Error> {
+        let failed = || ScoopieError::FailedToWriteFile(self.to_path_buf());
+
+        // Unique across processes and threads (buckets are synced in parallel).
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let suffix = format!(
+            "{}-{}",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let file_name = self.file_name().ok_or_else(failed)?.to_string_lossy();
+        let tmp_path = self.with_file_name(format!(".{file_name}.{suffix}.tmp"));
+
+        let written = File::create(&tmp_path).and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        });
+
+        match written.and_then(|_| fs::rename(&tmp_path, self)) {
+            Ok(()) => Ok(()),
+            Err(_) => {
+                let _ = remove_file(&tmp_path);
+                Err(failed())
+            }
+        }
+    }
+}
+
 pub trait CreateDir {
     type Error;
     fn create(path: &Self) -> Result<(), Self::Error>;
@@ -49,11 +99,28 @@ impl CreateDir for PathBuf {
     fn create(path: &Self) -> Result<(), Self::Error> {
         DirBuilder::new()
             .recursive(true)
-            .create(&path)
+            .create(path)
             .map_err(|_| ScoopieError::FailedToMkdir(path.to_path_buf()))
     }
 }
 
+/// `path` as a relative path that can't leave the directory it gets joined to, for paths coming
+/// from manifests and archives. `None` when it's absolute or goes up with `..`, a `:` would make
+/// it a drive (or an alternate data stream) on Windows.
+pub fn contained(path: &str) -> Option<PathBuf> {
+    if path.starts_with(['/', '\\']) {
+        return None;
+    }
+
+    path.split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != ".")
+        .map(|part| match part == ".." || part.contains(':') {
+            true => None,
+            false => Some(part),
+        })
+        .collect()
+}
+
 pub trait Absolute {
     type Error;
     fn absolute(&self) -> Result<PathBuf, Self::Error>;
@@ -77,3 +144,29 @@ impl Absolute for PathBuf {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contained_paths() {
+        assert_eq!(
+            contained("data\\config.ini"),
+            Some(PathBuf::from("data").join("config.ini"))
+        );
+        assert_eq!(contained("./data/"), Some(PathBuf::from("data")));
+        assert_eq!(contained(""), Some(PathBuf::new()));
+
+        [
+            "/etc",
+            "\\data",
+            "C:\\data",
+            "..",
+            "data/../../x",
+            "data\\..\\..",
+        ]
+        .iter()
+        .for_each(|path| assert_eq!(contained(path), None, "{path:?} was accepted"));
+    }
+}
@@ -26,42 +26,26 @@ impl Pwsh {
         }
     }
 
-    pub fn create_or_update(key: &str, value: &str) -> Result<(), ScoopieError> {
-        let cmd = Command::new("powershell.exe")
-            .args([
-                "-NoLogo",
-                "-NoProfile",
-                "-NonInteractive",
-                "-Command",
-                &format!("reg add HKCU\\Environment /v {key} /t REG_SZ /d {value} /f"),
-            ])
-            .output()
-            .map_err(|_| ScoopieError::UnableToExecuteCmd)?;
-
-        if cmd.status.success() {
-            Ok(())
-        } else {
-            Err(ScoopieError::EnvSet)
-        }
+    /// Quotes `value` as a PowerShell string literal that's never interpolated.
+    pub fn quote(value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
     }
 
-    pub fn remove(key: &str) -> Result<(), ScoopieError> {
-        let cmd = Command::new("powershell.exe")
-            .args([
-                "-NoLogo",
-                "-NoProfile",
-                "-NonInteractive",
-                "-Command",
-                &format!("reg add HKCU\\Environment /v {key} /f"),
-            ])
+    /// Runs `prog` and returns whether it succeeded along with everything it wrote to stdout and stderr.
+    pub fn exec(prog: &str) -> Result<(bool, String), ScoopieError> {
+        let result = Command::new("powershell.exe")
+            .args(["-NoLogo", "-NoProfile", "-NonInteractive", "-Command", prog])
             .output()
             .map_err(|_| ScoopieError::UnableToExecuteCmd)?;
 
-        if cmd.status.success() {
-            Ok(())
-        } else {
-            Err(ScoopieError::EnvRemove)
-        }
+        let output = [result.stdout, result.stderr]
+            .iter()
+            .map(|out| String::from_utf8_lossy(out).trim_end().to_string())
+            .filter(|out| !out.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Ok((result.status.success(), output))
     }
 
     pub fn run(profile: Option<&String>, prog: &str) -> Result<String, ScoopieError> {
@@ -80,10 +64,14 @@ impl Pwsh {
             None => vec!["-NoLogo", "-NoProfile", "-NonInteractive", "-Command", prog],
         };
 
-        let cmd = cmd.args(args);
-
-        let result = cmd.output().unwrap();
+        let result = cmd
+            .args(args)
+            .output()
+            .map_err(|_| ScoopieError::UnableToExecuteCmd)?;
 
-        Ok(String::from_utf8(result.stdout).unwrap())
+        match result.status.success() {
+            true => String::from_utf8(result.stdout).map_err(|_| ScoopieError::NonUTF8Bytes),
+            false => Err(ScoopieError::UnableToExecuteCmd),
+        }
     }
 }
@@ -0,0 +1,51 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Once,
+};
+
+use crate::core::buckets::Manifest;
+
+/// Scoopie home shared by every test, `$SCOOPIE_HOME` is set once for the whole run as tests
+/// run in parallel. What an earlier run left behind is wiped when it's first used.
+fn test_home() -> PathBuf {
+    static INIT: Once = Once::new();
+
+    let home = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("test-home");
+
+    INIT.call_once(|| {
+        let _ = fs::remove_dir_all(&home);
+
+        ["apps", "buckets", "cache", "persists", "shims"]
+            .iter()
+            .for_each(|dir| fs::create_dir_all(home.join(dir)).unwrap());
+
+        env::set_var("SCOOPIE_HOME", &home);
+    });
+
+    home
+}
+
+/// A fresh, empty `name` dir inside the test home, each test should use its own.
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = test_home().join("tests").join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// A manifest with just the required properties and `fields` (given as JSON object members).
+pub fn test_manifest(fields: &str) -> Manifest {
+    let fields = match fields.trim() {
+        "" => String::new(),
+        fields => format!(", {fields}"),
+    };
+
+    let json = format!(
+        r#"{{"version": "1.2", "description": "", "homepage": "", "license": "MIT"{fields}}}"#
+    );
+
+    Manifest::try_from(json.as_bytes()).unwrap()
+}